@@ -0,0 +1,112 @@
+//! Target acquisition: every tower scanning every enemy, as towers used to, against range
+//! queries on the spatial hash rebuilt once per tick.
+//!
+//! Run with `cargo bench --bench targeting`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use tower_defense::entities::Grunt;
+use tower_defense::level::Point;
+use tower_defense::slot_map::SlotMap;
+use tower_defense::spatial::SpatialHash;
+use tower_defense::towers::{TargetingMode, Tower, TowerType};
+use tower_defense::waves::EnemyKind;
+
+/// Side of the square level, in cells.
+const LEVEL_SIZE: usize = 100;
+/// (towers, enemies) per scenario.
+const SCENARIOS: [(usize, usize); 3] = [(50, 200), (200, 1000), (500, 5000)];
+
+fn towers(count: usize) -> Vec<Tower> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let attackers: Vec<TowerType> = TowerType::ALL.into_iter().filter(|t| t.attacks()).collect();
+    (0..count)
+        .map(|index| {
+            let position = (rng.gen_range(0..LEVEL_SIZE), rng.gen_range(0..LEVEL_SIZE));
+            let mut tower = Tower::new(position, attackers[index % attackers.len()]);
+            for _ in 0..index % 5 {
+                tower.targeting = tower.targeting.next();
+            }
+            tower
+        })
+        .collect()
+}
+
+/// The same enemies for every call with the same count.
+fn enemies(count: usize) -> impl Iterator<Item = Grunt> {
+    let mut rng = StdRng::seed_from_u64(11);
+    (0..count).map(move |id| {
+        let mut enemy = Grunt::new(id as u32, EnemyKind::Grunt, Point { x: 0, y: 0 }, rng.gen_range(1.0..100.0), 1.0, 1, 1);
+        enemy.position = (rng.gen_range(0.0..LEVEL_SIZE as f32), rng.gen_range(0.0..LEVEL_SIZE as f32));
+        enemy.path_index = rng.gen_range(0..LEVEL_SIZE);
+        enemy
+    })
+}
+
+/// `Tower::find_targets` before the spatial hash.
+fn find_targets_by_scan(tower: &Tower, enemies: &[Grunt]) -> Vec<usize> {
+    let mut targets = Vec::new();
+    let tower_pos = (tower.position.0 as f32 + 0.5, tower.position.1 as f32 + 0.5);
+
+    for (index, enemy) in enemies.iter().enumerate() {
+        let dx = tower_pos.0 - enemy.position.0;
+        let dy = tower_pos.1 - enemy.position.1;
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance <= tower.range {
+            targets.push(index);
+        }
+    }
+
+    match tower.targeting {
+        TargetingMode::Closest => targets.sort_by(|&a, &b| {
+            let dist_a = (tower_pos.0 - enemies[a].position.0).powi(2) + (tower_pos.1 - enemies[a].position.1).powi(2);
+            let dist_b = (tower_pos.0 - enemies[b].position.0).powi(2) + (tower_pos.1 - enemies[b].position.1).powi(2);
+            dist_a.partial_cmp(&dist_b).unwrap()
+        }),
+        TargetingMode::First => targets.sort_by(|&a, &b| enemies[b].progress().partial_cmp(&enemies[a].progress()).unwrap()),
+        TargetingMode::Last => targets.sort_by(|&a, &b| enemies[a].progress().partial_cmp(&enemies[b].progress()).unwrap()),
+        TargetingMode::Strongest => targets.sort_by(|&a, &b| enemies[b].health.partial_cmp(&enemies[a].health).unwrap()),
+        TargetingMode::Weakest => targets.sort_by(|&a, &b| enemies[a].health.partial_cmp(&enemies[b].health).unwrap()),
+    }
+
+    targets.truncate(tower.tower_type.max_targets());
+    targets
+}
+
+fn targeting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("targeting");
+    for (tower_count, enemy_count) in SCENARIOS {
+        let towers = towers(tower_count);
+        let label = format!("{}x{}", tower_count, enemy_count);
+
+        let enemy_list: Vec<Grunt> = enemies(enemy_count).collect();
+        group.bench_with_input(BenchmarkId::new("scan", &label), &(), |b, _| {
+            b.iter(|| {
+                for tower in &towers {
+                    black_box(find_targets_by_scan(tower, &enemy_list));
+                }
+            })
+        });
+
+        let mut enemy_map = SlotMap::new();
+        for enemy in enemies(enemy_count) {
+            enemy_map.insert(enemy);
+        }
+        let mut grid = SpatialHash::new(LEVEL_SIZE, LEVEL_SIZE);
+        group.bench_with_input(BenchmarkId::new("spatial_hash", &label), &(), |b, _| {
+            b.iter(|| {
+                grid.rebuild(&enemy_map);
+                for tower in &towers {
+                    black_box(tower.find_targets(&enemy_map, &grid));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, targeting);
+criterion_main!(benches);
@@ -0,0 +1,106 @@
+//! Static analysis of levels: how much of the path a tower would reach from each cell.
+
+use crate::level::{Level, Point};
+
+/// Points sampled along each step of the path when measuring the path length in range.
+const SAMPLES_PER_STEP: usize = 8;
+
+/// How much of the path a tower on one cell reaches.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct CellCoverage {
+    /// Path cells whose center is in range.
+    pub path_cells: u32,
+    /// Length of the path in range, in cells.
+    pub path_length: f32,
+}
+
+impl CellCoverage {
+    /// Seconds an enemy moving at `enemy_speed` cells per second spends in range.
+    pub fn time_in_range(&self, enemy_speed: f32) -> f32 {
+        self.path_length / enemy_speed
+    }
+}
+
+/// Path coverage of every buildable cell of a level for one tower range.
+pub struct CoverageMap {
+    pub width: usize,
+    pub height: usize,
+    pub range: f32,
+    /// Row-major; `None` on path cells, which cannot be built on.
+    cells: Vec<Option<CellCoverage>>,
+    best: CellCoverage,
+}
+
+impl CoverageMap {
+    pub fn new(level: &Level, range: f32) -> Self {
+        Self::from_path(level.width, level.height, &level.path, range)
+    }
+
+    /// Coverage of a `width` by `height` grid crossed by `path`, for towers reaching `range`
+    /// cells from the center of their cell.
+    pub fn from_path(width: usize, height: usize, path: &[Point], range: f32) -> Self {
+        let mut cells = vec![Some(CellCoverage::default()); width * height];
+        for point in path {
+            cells[point.y * width + point.x] = None;
+        }
+
+        let centers: Vec<(f32, f32)> = path.iter().map(|p| (p.x as f32 + 0.5, p.y as f32 + 0.5)).collect();
+        // Midpoints of equal slices of every step, each standing for its slice of the path
+        let mut samples = Vec::with_capacity(centers.len().saturating_sub(1) * SAMPLES_PER_STEP);
+        for step in centers.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt() / SAMPLES_PER_STEP as f32;
+            for sample in 0..SAMPLES_PER_STEP {
+                let t = (sample as f32 + 0.5) / SAMPLES_PER_STEP as f32;
+                samples.push((from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t, length));
+            }
+        }
+
+        let range_squared = range * range;
+        let in_range = |x: usize, y: usize, (px, py): (f32, f32)| {
+            let dx = px - (x as f32 + 0.5);
+            let dy = py - (y as f32 + 0.5);
+            dx * dx + dy * dy <= range_squared
+        };
+        let mut best = CellCoverage::default();
+        for y in 0..height {
+            for x in 0..width {
+                let Some(cell) = cells[y * width + x].as_mut() else {
+                    continue;
+                };
+                cell.path_cells = centers.iter().filter(|&&center| in_range(x, y, center)).count() as u32;
+                cell.path_length = samples
+                    .iter()
+                    .filter(|&&(px, py, _)| in_range(x, y, (px, py)))
+                    .map(|&(_, _, length)| length)
+                    .sum();
+                if cell.path_length > best.path_length {
+                    best = *cell;
+                }
+            }
+        }
+
+        CoverageMap { width, height, range, cells, best }
+    }
+
+    /// Coverage from a cell, or `None` for path cells and cells outside the grid.
+    pub fn get(&self, position: (usize, usize)) -> Option<CellCoverage> {
+        if position.0 >= self.width || position.1 >= self.height {
+            return None;
+        }
+        self.cells[position.1 * self.width + position.0]
+    }
+
+    /// The coverage of the cell reaching the longest stretch of path.
+    pub fn best(&self) -> CellCoverage {
+        self.best
+    }
+
+    /// Every buildable cell with its coverage, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), CellCoverage)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| cell.map(|cell| ((index % self.width, index / self.width), cell)))
+    }
+}
@@ -0,0 +1,313 @@
+//! Balance simulation: plays many headless games with bots across seeds, difficulties and modes
+//! in parallel, and writes one report row per game.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use tower_defense::bot::{self, BotKind};
+use tower_defense::game_controller::Outcome;
+use tower_defense::game_stats::GameStats;
+use tower_defense::settings::{Difficulty, GameMode, Settings};
+use tower_defense::towers::TowerType;
+
+const USAGE: &str = "\
+Usage: td-sim [OPTIONS] [GAME OPTIONS]
+
+Options:
+  --games <N>              Seeds to play, counting up from --first-seed (default: 100)
+  --first-seed <N>         First seed of the sweep (default: 1)
+  --bots <LIST>            Comma-separated bots (default: greedy,coverage)
+  --difficulties <LIST>    Comma-separated difficulties (default: normal)
+  --modes <LIST>           Comma-separated modes (default: classic)
+  --max-waves <N>          Stop a game as the wave after N starts, so endless games end
+                           (default: 100)
+  --threads <N>            Games played at once (default: one per CPU)
+  --format <csv|json>      Report format (default: csv)
+  --output <PATH>          Report file (default: standard output)
+  -h, --help               Print this help
+
+Every other option is passed on to the game's settings, e.g. --config, --waves, --grid
+or --money. Run `tower_defense --help` for the list.";
+
+struct SweepOptions {
+    games: u64,
+    first_seed: u64,
+    bots: Vec<BotKind>,
+    difficulties: Vec<Difficulty>,
+    modes: Vec<GameMode>,
+    max_waves: u32,
+    threads: usize,
+    json: bool,
+    output: Option<PathBuf>,
+    /// Options for `Settings::load`.
+    game_args: Vec<String>,
+}
+
+/// One game of the sweep.
+struct Job {
+    seed: u64,
+    bot: BotKind,
+    difficulty: Difficulty,
+    mode: GameMode,
+}
+
+/// Damage and kills of one tower type in a game.
+#[derive(Serialize)]
+struct TowerTypeResult {
+    tower_type: TowerType,
+    towers_built: u32,
+    damage_dealt: f32,
+    kills: u32,
+}
+
+#[derive(Serialize)]
+struct GameResult {
+    seed: u64,
+    bot: &'static str,
+    difficulty: Difficulty,
+    mode: GameMode,
+    /// victory, defeat, or capped when stopped by `--max-waves`.
+    outcome: &'static str,
+    waves_survived: u32,
+    kills: u32,
+    leaks: u32,
+    score: u32,
+    money_left: u32,
+    money_spent: u32,
+    /// Money in hand at the start of every wave.
+    money_by_wave: Vec<u32>,
+    tower_types: Vec<TowerTypeResult>,
+}
+
+impl GameResult {
+    fn new(job: &Job, outcome: Option<Outcome>, game_stats: &GameStats) -> Self {
+        let name = match outcome {
+            Some(Outcome::Victory) => "victory",
+            Some(Outcome::Defeat) => "defeat",
+            None => "capped",
+        };
+        // A capped game is scored for the waves it got through, like a lost one
+        let outcome = outcome.unwrap_or(Outcome::Defeat);
+        GameResult {
+            seed: job.seed,
+            bot: job.bot.name(),
+            difficulty: job.difficulty,
+            mode: job.mode,
+            outcome: name,
+            waves_survived: game_stats.waves_survived(outcome),
+            kills: game_stats.enemies_killed,
+            leaks: game_stats.enemies_leaked,
+            score: game_stats.score(outcome),
+            money_left: game_stats.economy.money(),
+            money_spent: game_stats.economy.total_spent(),
+            money_by_wave: game_stats.waves.iter().map(|wave| wave.money_at_start).collect(),
+            tower_types: game_stats
+                .by_tower_type()
+                .into_iter()
+                .map(|summary| TowerTypeResult {
+                    tower_type: summary.tower_type,
+                    towers_built: summary.towers_built,
+                    damage_dealt: summary.damage_dealt,
+                    kills: summary.kills,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap_or_else(|err| exit_with_error(err));
+    let settings = Settings::load(options.game_args.clone()).unwrap_or_else(|err| exit_with_error(err));
+
+    let mut jobs = Vec::new();
+    for seed in options.first_seed..options.first_seed + options.games {
+        for &difficulty in &options.difficulties {
+            for &mode in &options.modes {
+                for &bot in &options.bots {
+                    jobs.push(Job { seed, bot, difficulty, mode });
+                }
+            }
+        }
+    }
+
+    let started = Instant::now();
+    let results = run_jobs(&settings, &jobs, options.threads, options.max_waves);
+    eprintln!("played {} games in {:.1}s", results.len(), started.elapsed().as_secs_f32());
+
+    let written = match &options.output {
+        Some(path) => File::create(path).and_then(|file| write_report(BufWriter::new(file), &results, options.json)),
+        None => write_report(io::stdout().lock(), &results, options.json),
+    };
+    if let Err(err) = written {
+        exit_with_error(format!("could not write the report: {}", err));
+    }
+}
+
+/// Plays every job on `threads` worker threads and returns the results in job order.
+fn run_jobs(settings: &Settings, jobs: &[Job], threads: usize, max_waves: u32) -> Vec<GameResult> {
+    let next_job = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(jobs.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let index = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(index) else {
+                    break;
+                };
+                let mut game_settings = settings.clone();
+                game_settings.seed = job.seed;
+                game_settings.difficulty = job.difficulty;
+                game_settings.mode = job.mode;
+                let (outcome, game_stats) = bot::run_headless(&game_settings, job.bot, max_waves);
+                let result = GameResult::new(job, outcome, &game_stats);
+
+                let mut results = results.lock().expect("no worker panics while holding the lock");
+                results.push((index, result));
+                if results.len() % 100 == 0 {
+                    eprintln!("{}/{} games", results.len(), jobs.len());
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().expect("no worker panicked");
+    results.sort_by_key(|&(index, _)| index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn write_report(mut out: impl Write, results: &[GameResult], json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut out, results)?;
+        writeln!(out)?;
+        return out.flush();
+    }
+
+    write!(
+        out,
+        "seed,bot,difficulty,mode,outcome,waves_survived,kills,leaks,score,money_left,money_spent"
+    )?;
+    for tower_type in TowerType::ALL {
+        let name = tower_type.name().to_lowercase().replace(' ', "_");
+        write!(out, ",{0}_built,{0}_damage,{0}_kills", name)?;
+    }
+    writeln!(out, ",money_by_wave")?;
+
+    for result in results {
+        write!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            result.seed,
+            result.bot,
+            result.difficulty.name().to_lowercase(),
+            result.mode.name().to_lowercase(),
+            result.outcome,
+            result.waves_survived,
+            result.kills,
+            result.leaks,
+            result.score,
+            result.money_left,
+            result.money_spent
+        )?;
+        for tower_type in &result.tower_types {
+            write!(
+                out,
+                ",{},{:.0},{}",
+                tower_type.towers_built, tower_type.damage_dealt, tower_type.kills
+            )?;
+        }
+        let money_by_wave: Vec<String> = result.money_by_wave.iter().map(u32::to_string).collect();
+        writeln!(out, ",{}", money_by_wave.join(";"))?;
+    }
+    out.flush()
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<SweepOptions, String> {
+    let mut options = SweepOptions {
+        games: 100,
+        first_seed: 1,
+        bots: BotKind::ALL.to_vec(),
+        difficulties: vec![Difficulty::Normal],
+        modes: vec![GameMode::Classic],
+        max_waves: bot::DEFAULT_MAX_WAVES,
+        threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        json: false,
+        output: None,
+        game_args: Vec::new(),
+    };
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let sweep_flag = matches!(
+            flag.as_str(),
+            "--games"
+                | "--first-seed"
+                | "--bots"
+                | "--difficulties"
+                | "--modes"
+                | "--max-waves"
+                | "--threads"
+                | "--format"
+                | "--output"
+        );
+        if !sweep_flag {
+            match flag.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => options.game_args.push(arg),
+            }
+            continue;
+        }
+        let value = inline_value
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+
+        match flag.as_str() {
+            "--games" => options.games = parse_number(&flag, &value)?,
+            "--first-seed" => options.first_seed = parse_number(&flag, &value)?,
+            "--bots" => options.bots = parse_list(&value)?,
+            "--difficulties" => options.difficulties = parse_list(&value)?,
+            "--modes" => options.modes = parse_list(&value)?,
+            "--max-waves" => options.max_waves = parse_number(&flag, &value)?,
+            "--threads" => options.threads = parse_number(&flag, &value)?,
+            "--format" => {
+                options.json = match value.trim() {
+                    "csv" => false,
+                    "json" => true,
+                    _ => return Err(format!("--format expects csv or json, got '{}'", value)),
+                }
+            }
+            "--output" => options.output = Some(PathBuf::from(value)),
+            _ => unreachable!("only sweep flags get here"),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{} expects a number, got '{}'", flag, value))
+}
+
+fn parse_list<T: std::str::FromStr<Err = String>>(value: &str) -> Result<Vec<T>, String> {
+    value.split(',').map(str::parse).collect()
+}
+
+fn exit_with_error(err: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(2);
+}
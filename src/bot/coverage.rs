@@ -0,0 +1,106 @@
+use crate::analysis::CoverageMap;
+use crate::towers::{TargetingMode, Tower, TowerType};
+
+use super::{Bot, Command, Observation};
+
+/// Waves in which the bot saves up for a bank, once it has `BANK_DEFENDERS` towers.
+const BANK_WAVES: u32 = 2;
+const BANK_DEFENDERS: usize = 2;
+/// Share of the path after which an enemy is close enough to the exit for the bot to sell its
+/// banks and spend the money on defence.
+const PANIC_PROGRESS: f32 = 0.8;
+
+/// Builds whichever tower covers the longest stretch of path per unit of money, where it covers
+/// the most, and upgrades once no free cell is in range of the path.
+#[derive(Default)]
+pub struct CoverageBot {
+    /// Path coverage per tower type. The path never changes, so each map is computed once.
+    coverage: Vec<(TowerType, CoverageMap)>,
+}
+
+impl CoverageBot {
+    fn coverage(&mut self, observation: &Observation, tower_type: TowerType) -> &CoverageMap {
+        let index = match self.coverage.iter().position(|(t, _)| *t == tower_type) {
+            Some(index) => index,
+            None => {
+                let range = Tower::new((0, 0), tower_type).range;
+                let map = CoverageMap::from_path(observation.width, observation.height, observation.path, range);
+                self.coverage.push((tower_type, map));
+                self.coverage.len() - 1
+            }
+        };
+        &self.coverage[index].1
+    }
+
+    /// The free cell and attacking tower type with the most path coverage times damage per
+    /// second per unit of money, if any free cell reaches the path. The flag tells whether the
+    /// bot can afford it.
+    fn best_build(&mut self, observation: &Observation) -> Option<((usize, usize), TowerType, bool)> {
+        let mut best: Option<((usize, usize), TowerType, f32)> = None;
+        for tower_type in TowerType::ALL.into_iter().filter(|t| t.attacks()) {
+            let stats = Tower::new((0, 0), tower_type);
+            let damage_rate = stats.damage * stats.fire_rate * tower_type.max_targets() as f32;
+            let value_per_cell = damage_rate / tower_type.cost() as f32;
+            let coverage = self.coverage(observation, tower_type);
+            for position in observation.free_cells() {
+                let value = coverage.get(position).map_or(0.0, |cell| cell.path_length) * value_per_cell;
+                if value > best.map_or(0.0, |(_, _, best_value)| best_value) {
+                    best = Some((position, tower_type, value));
+                }
+            }
+        }
+        best.map(|(position, tower_type, _)| (position, tower_type, observation.money >= tower_type.cost()))
+    }
+}
+
+impl Bot for CoverageBot {
+    fn decide(&mut self, observation: &Observation) -> Vec<Command> {
+        let bank = observation.towers.values().find(|t| !t.tower_type.attacks());
+        let exit = observation.path.len().saturating_sub(1) as f32;
+        let panic = observation.enemies.values().any(|e| e.progress() >= exit * PANIC_PROGRESS);
+        if let Some(bank) = bank.filter(|_| panic && observation.wave > BANK_WAVES) {
+            return vec![Command::Sell { position: bank.position }];
+        }
+
+        let defenders = observation.towers.values().filter(|t| t.tower_type.attacks()).count();
+        if bank.is_none() && observation.wave <= BANK_WAVES && defenders >= BANK_DEFENDERS {
+            // Banks do not need the path, so take the spot a gun would use least
+            let coverage = self.coverage(observation, TowerType::Gun);
+            let spot = observation
+                .free_cells()
+                .min_by(|&a, &b| {
+                    let path_length = |position| coverage.get(position).map_or(0.0, |cell| cell.path_length);
+                    path_length(a).total_cmp(&path_length(b))
+                });
+            if let Some(position) = spot {
+                // Save up for it rather than spend on more defenders
+                return if observation.money >= TowerType::Bank.cost() {
+                    vec![Command::Build { position, tower_type: TowerType::Bank }]
+                } else {
+                    Vec::new()
+                };
+            }
+        }
+
+        match self.best_build(observation) {
+            Some((position, tower_type, true)) => {
+                let mut commands = vec![Command::Build { position, tower_type }];
+                if tower_type == TowerType::Sniper {
+                    commands.push(Command::SetTargeting { position, mode: TargetingMode::Strongest });
+                }
+                commands
+            }
+            // Save up for it
+            Some((_, _, false)) => Vec::new(),
+            None => observation
+                .towers
+                .values()
+                .filter(|tower| tower.tower_type.attacks())
+                .filter_map(|tower| tower.upgrade_cost().map(|cost| (cost, tower.position)))
+                .filter(|&(cost, _)| cost <= observation.money)
+                .min_by_key(|&(cost, _)| cost)
+                .map(|(_, position)| vec![Command::Upgrade { position }])
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::towers::{Tower, TowerType};
+
+use super::{Bot, Cell, Command, Observation};
+
+/// Buys the cheapest attacking tower as soon as it can afford one, next to the stretch of path
+/// closest to the exit that no tower covers yet, so its towers spread out along the path. Once
+/// every stretch is covered it upgrades the cheapest tower.
+pub struct GreedyBot;
+
+impl Bot for GreedyBot {
+    fn decide(&mut self, observation: &Observation) -> Vec<Command> {
+        let cheapest = TowerType::ALL
+            .into_iter()
+            .filter(|tower_type| tower_type.attacks())
+            .min_by_key(|tower_type| tower_type.cost())
+            .expect("there is at least one attacking tower type");
+
+        let range = Tower::new((0, 0), cheapest).range;
+        let covered = |x: usize, y: usize| {
+            observation.towers.values().any(|tower| {
+                let dx = tower.position.0 as f32 - x as f32;
+                let dy = tower.position.1 as f32 - y as f32;
+                (dx * dx + dy * dy).sqrt() <= tower.range.max(range)
+            })
+        };
+        let spot = observation.path.iter().rev().filter(|point| !covered(point.x, point.y)).find_map(|point| {
+            neighbours((point.x, point.y), observation.width, observation.height)
+                .find(|&position| observation.cell(position) == Cell::Free)
+        });
+        if let Some(position) = spot {
+            return if observation.money >= cheapest.cost() {
+                vec![Command::Build { position, tower_type: cheapest }]
+            } else {
+                Vec::new()
+            };
+        }
+
+        observation
+            .towers
+            .values()
+            .filter_map(|tower| tower.upgrade_cost().map(|cost| (cost, tower.position)))
+            .filter(|&(cost, _)| cost <= observation.money)
+            .min_by_key(|&(cost, _)| cost)
+            .map(|(_, position)| vec![Command::Upgrade { position }])
+            .unwrap_or_default()
+    }
+}
+
+/// The up to eight cells around a cell that lie on the grid.
+fn neighbours(position: (usize, usize), width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (x, y) = (position.0 as i64, position.1 as i64);
+    (-1..=1)
+        .flat_map(move |dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+        .filter(move |&(nx, ny)| {
+            (nx, ny) != (x, y) && nx >= 0 && ny >= 0 && nx < width as i64 && ny < height as i64
+        })
+        .map(|(nx, ny)| (nx as usize, ny as usize))
+}
@@ -0,0 +1,202 @@
+mod coverage;
+mod greedy;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::entities::Grunt;
+use crate::events;
+use crate::game_controller::{GameController, Outcome};
+use crate::game_stats::GameStats;
+use crate::level::Point;
+use crate::settings::Settings;
+use crate::slot_map::SlotMap;
+use crate::towers::{TargetingMode, Tower, TowerType};
+
+pub use coverage::CoverageBot;
+pub use greedy::GreedyBot;
+
+/// Seconds of game time between two decisions of a bot.
+pub const DECISION_INTERVAL: f32 = 0.5;
+/// Length of one simulation step when running without a window.
+const HEADLESS_STEP: f32 = 1.0 / 30.0;
+/// Waves after which a headless game is stopped, so endless games a bot keeps winning end.
+pub const DEFAULT_MAX_WAVES: u32 = 100;
+
+/// What occupies a grid cell.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cell {
+    Free,
+    Path,
+    Tower,
+}
+
+/// Everything a bot can see of the game when it makes a decision.
+pub struct Observation<'a> {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major, `width * height` cells.
+    pub occupancy: Vec<Cell>,
+    pub path: &'a [Point],
+    pub enemies: &'a SlotMap<Grunt>,
+    pub towers: &'a SlotMap<Tower>,
+    pub money: u32,
+    pub wave: u32,
+}
+
+impl<'a> Observation<'a> {
+    pub fn new(game_controller: &'a GameController) -> Self {
+        let level = &game_controller.level;
+        let mut occupancy = vec![Cell::Free; level.width * level.height];
+        for point in &level.path {
+            occupancy[point.y * level.width + point.x] = Cell::Path;
+        }
+        for tower in game_controller.towers.values() {
+            occupancy[tower.position.1 * level.width + tower.position.0] = Cell::Tower;
+        }
+        Observation {
+            width: level.width,
+            height: level.height,
+            occupancy,
+            path: &level.path,
+            enemies: &game_controller.enemies,
+            towers: &game_controller.towers,
+            money: game_controller.game_stats.economy.money(),
+            wave: game_controller.game_stats.wave,
+        }
+    }
+
+    pub fn cell(&self, position: (usize, usize)) -> Cell {
+        self.occupancy[position.1 * self.width + position.0]
+    }
+
+    /// Every cell a tower can be built on, row by row.
+    pub fn free_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(move |&position| self.cell(position) == Cell::Free)
+    }
+}
+
+/// An order from a bot, carried out like the matching player input.
+#[derive(Clone, Copy)]
+pub enum Command {
+    Build { position: (usize, usize), tower_type: TowerType },
+    Upgrade { position: (usize, usize) },
+    Sell { position: (usize, usize) },
+    SetTargeting { position: (usize, usize), mode: TargetingMode },
+}
+
+impl Command {
+    /// Carries out the command. Returns false when it was not possible, e.g. for lack of money.
+    pub fn apply(self, game_controller: &mut GameController) -> bool {
+        match self {
+            Command::Build { position, tower_type } => game_controller.add_tower(position, tower_type),
+            Command::Upgrade { position } => game_controller.upgrade_tower(position),
+            Command::Sell { position } => game_controller.sell_tower(position),
+            Command::SetTargeting { position, mode } => game_controller.set_targeting(position, mode),
+        }
+    }
+}
+
+/// A player that is not a human: given what it sees of the game, it decides what to do.
+pub trait Bot {
+    fn decide(&mut self, observation: &Observation) -> Vec<Command>;
+}
+
+/// The reference bots, as chosen with `--bot`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BotKind {
+    /// Buys the cheapest tower along the path whenever it can.
+    Greedy,
+    /// Builds where a tower covers the most path, and upgrades once no spot is left.
+    Coverage,
+}
+
+impl BotKind {
+    pub const ALL: [BotKind; 2] = [BotKind::Greedy, BotKind::Coverage];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BotKind::Greedy => "greedy",
+            BotKind::Coverage => "coverage",
+        }
+    }
+
+    pub fn create(self) -> Box<dyn Bot> {
+        match self {
+            BotKind::Greedy => Box::new(GreedyBot),
+            BotKind::Coverage => Box::new(CoverageBot::default()),
+        }
+    }
+}
+
+impl fmt::Display for BotKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for BotKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BotKind::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown bot '{}', expected greedy or coverage", s))
+    }
+}
+
+/// Lets a bot play a game, asking it for commands every `DECISION_INTERVAL` seconds of game
+/// time.
+pub struct BotDriver {
+    bot: Box<dyn Bot>,
+    timer: f32,
+}
+
+impl BotDriver {
+    pub fn new(kind: BotKind) -> Self {
+        BotDriver {
+            bot: kind.create(),
+            // The first decision is made right away
+            timer: DECISION_INTERVAL,
+        }
+    }
+
+    /// Call before `GameController::update` with the same time step.
+    pub fn update(&mut self, game_controller: &mut GameController, delta_time: f32) {
+        self.timer += delta_time;
+        if self.timer < DECISION_INTERVAL {
+            return;
+        }
+        self.timer = 0.0;
+        let commands = self.bot.decide(&Observation::new(game_controller));
+        for command in commands {
+            command.apply(game_controller);
+        }
+    }
+}
+
+/// Plays a whole game with a bot and no window, as fast as possible. The outcome is `None` when
+/// the game was stopped as wave `max_waves + 1` started.
+pub fn run_headless(settings: &Settings, kind: BotKind, max_waves: u32) -> (Option<Outcome>, GameStats) {
+    let mut game_controller = GameController::new(settings);
+    let mut driver = BotDriver::new(kind);
+    loop {
+        driver.update(&mut game_controller, HEADLESS_STEP);
+        game_controller.update(settings, HEADLESS_STEP);
+        let time = game_controller.total_time;
+        for event in game_controller.drain_events() {
+            if settings.log_events {
+                events::log(time, &event);
+            }
+        }
+        if let Some(outcome) = game_controller.outcome() {
+            return (Some(outcome), game_controller.game_stats);
+        }
+        if game_controller.game_stats.wave > max_waves {
+            return (None, game_controller.game_stats);
+        }
+    }
+}
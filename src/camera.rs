@@ -0,0 +1,118 @@
+use ggez::graphics::Rect;
+
+pub const MAX_ZOOM: f32 = 4.0;
+/// Pan speed for keyboard and edge scrolling, in screen pixels per second.
+pub const PAN_SPEED: f32 = 600.0;
+
+/// Maps between world coordinates (grid cells times the cell size) and screen coordinates.
+/// The visible part of the world is clamped to the level, and centered when the whole level
+/// fits in the viewport.
+pub struct Camera {
+    /// World position shown at the top-left corner of the viewport.
+    offset: (f32, f32),
+    zoom: f32,
+    /// Screen area the playfield is drawn into.
+    viewport: Rect,
+    world_size: (f32, f32),
+}
+
+impl Camera {
+    pub fn new(world_width: f32, world_height: f32, viewport: Rect) -> Self {
+        let mut camera = Camera {
+            offset: (0.0, 0.0),
+            zoom: 1.0,
+            viewport,
+            world_size: (world_width, world_height),
+        };
+        camera.zoom = camera.fit_zoom();
+        camera.clamp();
+        camera
+    }
+
+    /// The zoom at which the whole level fills the viewport.
+    fn fit_zoom(&self) -> f32 {
+        let fit = (self.viewport.w / self.world_size.0).min(self.viewport.h / self.world_size.1);
+        fit.min(MAX_ZOOM)
+    }
+
+    /// Players can always zoom out far enough to see the whole level, and down to 1:1 on levels
+    /// that fit the window anyway.
+    fn min_zoom(&self) -> f32 {
+        self.fit_zoom().min(1.0)
+    }
+
+    /// Moves the view to a new screen area after the window is resized. A view showing the
+    /// whole level keeps doing so; otherwise the zoom is kept and the offset clamped.
+    pub fn set_viewport(&mut self, viewport: Rect) {
+        let fitted = (self.zoom - self.fit_zoom()).abs() < 1e-3;
+        self.viewport = viewport;
+        self.zoom = if fitted {
+            self.fit_zoom()
+        } else {
+            self.zoom.clamp(self.min_zoom(), MAX_ZOOM)
+        };
+        self.clamp();
+    }
+
+    fn clamp(&mut self) {
+        self.offset.0 = clamp_axis(self.offset.0, self.world_size.0, self.viewport.w / self.zoom);
+        self.offset.1 = clamp_axis(self.offset.1, self.world_size.1, self.viewport.h / self.zoom);
+    }
+
+    /// Moves the view by a distance given in screen pixels.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.offset.0 += dx / self.zoom;
+        self.offset.1 += dy / self.zoom;
+        self.clamp();
+    }
+
+    /// Zooms by a factor while keeping the world point under `screen_point` in place.
+    pub fn zoom_at(&mut self, factor: f32, screen_point: (f32, f32)) {
+        let anchor = self.screen_to_world(screen_point.0, screen_point.1);
+        self.zoom = (self.zoom * factor).clamp(self.min_zoom(), MAX_ZOOM);
+        self.offset.0 = anchor.0 - (screen_point.0 - self.viewport.x) / self.zoom;
+        self.offset.1 = anchor.1 - (screen_point.1 - self.viewport.y) / self.zoom;
+        self.clamp();
+    }
+
+    pub fn screen_to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.viewport.x) / self.zoom + self.offset.0,
+            (y - self.viewport.y) / self.zoom + self.offset.1,
+        )
+    }
+
+    pub fn world_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.offset.0) * self.zoom + self.viewport.x,
+            (y - self.offset.1) * self.zoom + self.viewport.y,
+        )
+    }
+
+    /// The grid cell under a screen position, if the position is over the level.
+    pub fn screen_to_grid(&self, x: f32, y: f32, cell_size: f32) -> Option<(usize, usize)> {
+        if !self.viewport.contains([x, y]) {
+            return None;
+        }
+        let (world_x, world_y) = self.screen_to_world(x, y);
+        if world_x < 0.0 || world_y < 0.0 || world_x >= self.world_size.0 || world_y >= self.world_size.1 {
+            return None;
+        }
+        Some(((world_x / cell_size) as usize, (world_y / cell_size) as usize))
+    }
+
+    /// Screen coordinates to give a canvas covering `screen_width` x `screen_height` pixels so
+    /// that world-space draws end up where this camera shows them.
+    pub fn world_screen_coordinates(&self, screen_width: f32, screen_height: f32) -> Rect {
+        let top_left = self.screen_to_world(0.0, 0.0);
+        Rect::new(top_left.0, top_left.1, screen_width / self.zoom, screen_height / self.zoom)
+    }
+}
+
+fn clamp_axis(offset: f32, world: f32, visible: f32) -> f32 {
+    if visible >= world {
+        (world - visible) / 2.0
+    } else {
+        offset.clamp(0.0, world - visible)
+    }
+}
@@ -0,0 +1,261 @@
+use serde::Serialize;
+
+use crate::settings::{DifficultyModifiers, Settings};
+
+/// Shortest time between two samples of the money history, in seconds of game time.
+const HISTORY_INTERVAL: f32 = 0.5;
+
+/// Where money comes from.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Income {
+    Bounty,
+    WaveBonus,
+    Interest,
+    EarlyCall,
+    Bank,
+    /// Money returned by selling towers. Not counted as earnings.
+    Refund,
+}
+
+impl Income {
+    pub const ALL: [Income; 6] = [
+        Income::Bounty,
+        Income::WaveBonus,
+        Income::Interest,
+        Income::EarlyCall,
+        Income::Bank,
+        Income::Refund,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Income::Bounty => "Bounties",
+            Income::WaveBonus => "Wave bonuses",
+            Income::Interest => "Interest",
+            Income::EarlyCall => "Early calls",
+            Income::Bank => "Banks",
+            Income::Refund => "Refunds",
+        }
+    }
+
+    fn index(self) -> usize {
+        Income::ALL.iter().position(|&income| income == self).unwrap_or_default()
+    }
+}
+
+/// How much each source of income pays, taken from the settings and scaled for difficulty.
+#[derive(Clone, Copy)]
+pub struct EconomyRules {
+    base_bounty: u32,
+    bounty_per_wave: u32,
+    wave_bonus: u32,
+    wave_bonus_per_wave: u32,
+    interest_rate: f32,
+    interest_cap: u32,
+    early_call_bonus: f32,
+    bank_income: u32,
+    modifiers: DifficultyModifiers,
+}
+
+impl EconomyRules {
+    pub fn new(settings: &Settings, modifiers: &DifficultyModifiers) -> Self {
+        EconomyRules {
+            base_bounty: settings.enemy_kill_reward,
+            bounty_per_wave: settings.bounty_per_wave,
+            wave_bonus: settings.wave_bonus,
+            wave_bonus_per_wave: settings.wave_bonus_per_wave,
+            interest_rate: settings.interest_rate,
+            interest_cap: settings.interest_cap,
+            early_call_bonus: settings.early_call_bonus,
+            bank_income: settings.bank_income,
+            modifiers: *modifiers,
+        }
+    }
+
+    /// Money for killing an enemy of the given wave. Later enemies are worth more.
+    pub fn bounty(&self, wave: u32) -> u32 {
+        self.modifiers
+            .scale_bounty(self.base_bounty + self.bounty_per_wave * wave.saturating_sub(1))
+    }
+
+    /// Money for clearing every enemy of a wave.
+    pub fn wave_bonus(&self, wave: u32) -> u32 {
+        self.wave_bonus + self.wave_bonus_per_wave * wave.saturating_sub(1)
+    }
+
+    /// Interest paid on banked money at the end of a wave.
+    pub fn interest(&self, money: u32) -> u32 {
+        ((money as f32 * self.interest_rate) as u32).min(self.interest_cap)
+    }
+
+    /// Bonus for calling a wave while `seconds` of the break before it were left.
+    pub fn early_call_bonus(&self, seconds: f32) -> u32 {
+        (seconds.max(0.0) * self.early_call_bonus).round() as u32
+    }
+
+    /// Money a bank of the given level pays at the end of each wave.
+    pub fn bank_income(&self, level: u32) -> u32 {
+        (self.bank_income as f32 * (1.0 + 0.5 * level.saturating_sub(1) as f32)).round() as u32
+    }
+}
+
+/// The player's money: what comes in from each source, what goes out, and how the balance
+/// developed over the game.
+#[derive(Clone)]
+pub struct Economy {
+    money: u32,
+    earned: [u32; Income::ALL.len()],
+    spent: u32,
+    /// Balance over game time in seconds, sampled when it changes.
+    history: Vec<(f32, u32)>,
+    pub rules: EconomyRules,
+}
+
+impl Economy {
+    pub fn new(initial_money: u32, rules: EconomyRules) -> Self {
+        Economy {
+            money: initial_money,
+            earned: [0; Income::ALL.len()],
+            spent: 0,
+            history: vec![(0.0, initial_money)],
+            rules,
+        }
+    }
+
+    pub fn money(&self) -> u32 {
+        self.money
+    }
+
+    pub fn earn(&mut self, source: Income, amount: u32) {
+        self.money += amount;
+        self.earned[source.index()] += amount;
+    }
+
+    pub fn spend(&mut self, amount: u32) -> bool {
+        if self.money >= amount {
+            self.money -= amount;
+            self.spent += amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn earned_from(&self, source: Income) -> u32 {
+        self.earned[source.index()]
+    }
+
+    pub fn total_spent(&self) -> u32 {
+        self.spent
+    }
+
+    pub fn history(&self) -> &[(f32, u32)] {
+        &self.history
+    }
+
+    /// Adds a sample to the money history if the balance changed, at most once per
+    /// `HISTORY_INTERVAL` unless `force` is set.
+    pub fn record_history(&mut self, time: f32, force: bool) {
+        let (last_time, last_money) = *self.history.last().expect("history starts with a sample");
+        if last_money != self.money && (force || time - last_time >= HISTORY_INTERVAL) {
+            self.history.push((time, self.money));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Difficulty;
+
+    fn rules(difficulty: Difficulty, change: impl FnOnce(&mut Settings)) -> EconomyRules {
+        let mut settings = Settings::new();
+        settings.enemy_kill_reward = 10;
+        settings.bounty_per_wave = 2;
+        settings.wave_bonus = 20;
+        settings.wave_bonus_per_wave = 5;
+        settings.interest_rate = 0.1;
+        settings.interest_cap = 50;
+        settings.early_call_bonus = 2.0;
+        settings.bank_income = 30;
+        change(&mut settings);
+        EconomyRules::new(&settings, &difficulty.modifiers())
+    }
+
+    #[test]
+    fn bounty_grows_per_wave_and_scales_with_difficulty() {
+        let normal = rules(Difficulty::Normal, |_| {});
+        assert_eq!(normal.bounty(1), 10);
+        assert_eq!(normal.bounty(4), 16);
+        // Wave 0 does not exist, but must not underflow
+        assert_eq!(normal.bounty(0), 10);
+        // Easy pays a quarter more
+        assert_eq!(rules(Difficulty::Easy, |_| {}).bounty(1), 13);
+    }
+
+    #[test]
+    fn wave_bonus_grows_per_wave() {
+        let rules = rules(Difficulty::Normal, |_| {});
+        assert_eq!(rules.wave_bonus(1), 20);
+        assert_eq!(rules.wave_bonus(3), 30);
+    }
+
+    #[test]
+    fn interest_is_capped() {
+        let capped = rules(Difficulty::Normal, |_| {});
+        assert_eq!(capped.interest(0), 0);
+        assert_eq!(capped.interest(199), 19);
+        assert_eq!(capped.interest(500), 50);
+        assert_eq!(capped.interest(10_000), 50);
+        let no_interest = rules(Difficulty::Normal, |s| s.interest_rate = 0.0);
+        assert_eq!(no_interest.interest(10_000), 0);
+    }
+
+    #[test]
+    fn early_call_bonus_rounds_and_ignores_negative_time() {
+        let rules = rules(Difficulty::Normal, |_| {});
+        assert_eq!(rules.early_call_bonus(7.3), 15);
+        assert_eq!(rules.early_call_bonus(0.0), 0);
+        assert_eq!(rules.early_call_bonus(-3.0), 0);
+    }
+
+    #[test]
+    fn bank_income_grows_with_level() {
+        let rules = rules(Difficulty::Normal, |_| {});
+        assert_eq!(rules.bank_income(1), 30);
+        assert_eq!(rules.bank_income(2), 45);
+        assert_eq!(rules.bank_income(3), 60);
+    }
+
+    #[test]
+    fn tracks_earnings_and_spending() {
+        let mut economy = Economy::new(100, rules(Difficulty::Normal, |_| {}));
+        economy.earn(Income::Bounty, 10);
+        economy.earn(Income::Bounty, 12);
+        economy.earn(Income::Refund, 70);
+        assert!(economy.spend(150));
+        // Not enough left: nothing is taken
+        assert!(!economy.spend(100));
+
+        assert_eq!(economy.money(), 42);
+        assert_eq!(economy.earned_from(Income::Bounty), 22);
+        assert_eq!(economy.earned_from(Income::Refund), 70);
+        assert_eq!(economy.earned_from(Income::Interest), 0);
+        assert_eq!(economy.total_spent(), 150);
+    }
+
+    #[test]
+    fn history_samples_changes_at_an_interval() {
+        let mut economy = Economy::new(100, rules(Difficulty::Normal, |_| {}));
+        economy.earn(Income::Bounty, 10);
+        economy.record_history(0.1, false);
+        economy.record_history(0.6, false);
+        economy.earn(Income::Bounty, 10);
+        economy.record_history(0.7, false);
+        economy.record_history(0.8, true);
+        // Unchanged balances are never sampled
+        economy.record_history(5.0, true);
+        assert_eq!(economy.history(), &[(0.0, 100), (0.6, 110), (0.8, 120)]);
+    }
+}
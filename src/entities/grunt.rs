@@ -1,25 +1,38 @@
 use crate::level::Point;
-use super::healthbar::HealthBar;
+use crate::waves::EnemyKind;
+use super::HealthBar;
 
 pub struct Grunt {
+    /// Spawn number, counting from 0. Shown to the player and used to break ties in spawn
+    /// order.
+    pub id: u32,
+    pub kind: EnemyKind,
     pub position: (f32, f32),
     pub health: f32,
     pub speed: f32,
     pub path_index: usize,
     pub target: (f32, f32),
     pub health_bar: HealthBar,
+    /// The wave the enemy was spawned in.
+    pub wave: u32,
+    /// Money paid for killing the enemy.
+    pub bounty: u32,
 }
 
 impl Grunt {
-    pub fn new(start: Point, health: f32, speed: f32) -> Self {
+    pub fn new(id: u32, kind: EnemyKind, start: Point, health: f32, speed: f32, wave: u32, bounty: u32) -> Self {
         let position = (start.x as f32 + 0.5, start.y as f32 + 0.5);
         Grunt {
+            id,
+            kind,
             position,
             health,
             speed,
             path_index: 0,
             target: position,
             health_bar: HealthBar::new(health),
+            wave,
+            bounty,
         }
     }
 
@@ -47,4 +60,16 @@ impl Grunt {
 
         self.health_bar.update(self.health, self.position);
     }
+
+    /// How far along the path the enemy has travelled, in path cells.
+    pub fn progress(&self) -> f32 {
+        let dx = self.target.0 - self.position.0;
+        let dy = self.target.1 - self.position.1;
+        let remaining = (dx * dx + dy * dy).sqrt().min(1.0);
+        if remaining > 0.0 {
+            self.path_index as f32 + 1.0 - remaining
+        } else {
+            self.path_index as f32
+        }
+    }
 }
\ No newline at end of file
@@ -2,4 +2,8 @@ mod grunt;
 mod healthbar;
 
 pub use grunt::Grunt;
-pub use healthbar::HealthBar;
\ No newline at end of file
+pub use healthbar::HealthBar;
+
+use crate::slot_map::Handle;
+
+pub type EnemyId = Handle<Grunt>;
\ No newline at end of file
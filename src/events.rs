@@ -0,0 +1,59 @@
+//! Things that happen in a game, reported by `GameController` for whatever wants to react to
+//! them without reaching into the simulation: effects, sound, logging, and the combat
+//! statistics of `GameStats`. Money and lives are not derived from them; the controller keeps
+//! those up to date itself, as they decide the game.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::entities::EnemyId;
+use crate::towers::{TowerId, TowerType};
+
+/// Positions are in cells: enemy positions as they are drawn, tower positions as grid cells.
+#[derive(Clone, Copy)]
+pub enum GameEvent {
+    EnemySpawned { enemy: EnemyId, position: (f32, f32) },
+    /// One hit from a tower, on an enemy that had `health` left. The enemy may already be dead
+    /// from an earlier hit of the tick. `stats_id` is the tower's `Tower::stats_id`.
+    EnemyDamaged { enemy: EnemyId, tower: TowerId, stats_id: usize, damage: f32, health: f32, position: (f32, f32) },
+    /// `wave` is the wave the enemy was spawned in.
+    EnemyKilled { enemy: EnemyId, wave: u32, position: (f32, f32), bounty: u32 },
+    EnemyLeaked { enemy: EnemyId, wave: u32, position: (f32, f32), lives: u32 },
+    TowerBuilt { tower: TowerId, tower_type: TowerType, position: (usize, usize) },
+    /// A tower shot at `targets` enemies, each of which also gets an `EnemyDamaged`.
+    TowerFired { tower: TowerId, stats_id: usize, tower_type: TowerType, position: (usize, usize), targets: usize },
+    WaveStarted { wave: u32 },
+    /// Every enemy of the wave has been killed or has leaked, and its bonus has been paid.
+    WaveCleared { wave: u32 },
+    /// The balance after a tick or a player action changed it, and by how much.
+    MoneyChanged { money: u32, change: i64 },
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GameEvent::EnemySpawned { enemy, position } => {
+                write!(f, "enemy {:?} spawned at ({:.1}, {:.1})", enemy, position.0, position.1)
+            }
+            GameEvent::EnemyDamaged { enemy, tower, damage, .. } => {
+                write!(f, "enemy {:?} took {:.1} damage from tower {:?}", enemy, damage, tower)
+            }
+            GameEvent::EnemyKilled { enemy, bounty, .. } => write!(f, "enemy {:?} killed, bounty ${}", enemy, bounty),
+            GameEvent::EnemyLeaked { enemy, lives, .. } => write!(f, "enemy {:?} leaked, {} lives left", enemy, lives),
+            GameEvent::TowerBuilt { tower, tower_type, position } => {
+                write!(f, "{} {:?} built at ({}, {})", tower_type.name(), tower, position.0, position.1)
+            }
+            GameEvent::TowerFired { tower, tower_type, targets, .. } => {
+                write!(f, "{} {:?} fired at {} target(s)", tower_type.name(), tower, targets)
+            }
+            GameEvent::WaveStarted { wave } => write!(f, "wave {} started", wave),
+            GameEvent::WaveCleared { wave } => write!(f, "wave {} cleared", wave),
+            GameEvent::MoneyChanged { money, change } => write!(f, "money {:+} to ${}", change, money),
+        }
+    }
+}
+
+/// Prints an event to standard error, stamped with the game time, for `--log-events`.
+pub fn log(time: Duration, event: &GameEvent) {
+    eprintln!("[{:9.3}s] {}", time.as_secs_f32(), event);
+}
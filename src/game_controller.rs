@@ -1,28 +1,133 @@
-use crate::entities::Grunt;
+use crate::economy::{Economy, EconomyRules, Income};
+use crate::entities::{EnemyId, Grunt};
+use crate::events::GameEvent;
 use crate::level::Level;
-use crate::settings::Settings;
-use crate::towers::{Tower, TowerType};
+use crate::slot_map::SlotMap;
+use crate::spatial::SpatialHash;
+use crate::settings::{DifficultyModifiers, Settings};
+use crate::towers::{TargetingMode, Tower, TowerId, TowerType};
+use crate::waves::{EnemyKind, ScheduledSpawn};
 use crate::game_stats::GameStats;
 use std::time::Duration;
+use std::vec::Drain;
+
+use serde::Serialize;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Victory,
+    Defeat,
+}
+
+/// Why a tower cannot be built on a cell.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PlacementError {
+    OutOfBounds,
+    OnPath,
+    Occupied,
+    Unaffordable,
+}
+
+/// Where the game is in its wave schedule. `GameStats::wave` is the last wave started; waves
+/// called early spawn alongside the ones before them.
+#[derive(Clone, Copy, PartialEq)]
+pub enum WavePhase {
+    /// The first `spawned` enemies of `GameController::schedule` are out, `elapsed` seconds
+    /// after the schedule started.
+    Spawning { spawned: usize, elapsed: f32 },
+    /// Waiting `remaining` seconds before the next wave.
+    Break { remaining: f32 },
+    /// The last wave has been spawned.
+    Finished,
+}
+
+/// Summary of the enemies of one wave: how many there are of each kind and the toughest and
+/// fastest among them.
+#[derive(Clone)]
+pub struct WaveComposition {
+    pub wave: u32,
+    /// Enemies per kind, in the order the kinds first appear in the wave.
+    pub enemies: Vec<(EnemyKind, u32)>,
+    pub health: f32,
+    pub speed: f32,
+    pub bounty: u32,
+}
 
 pub struct GameController {
-    pub enemies: Vec<Grunt>,
-    pub towers: Vec<Tower>,
-    pub spawn_timer: f32,
+    pub enemies: SlotMap<Grunt>,
+    pub towers: SlotMap<Tower>,
+    pub wave_phase: WavePhase,
+    /// Every enemy of the waves being spawned, with its wave, in spawn order.
+    schedule: Vec<(u32, ScheduledSpawn)>,
+    /// Waves whose enemies have all been killed or leaked, and whose bonus has been paid.
+    completed_waves: u32,
+    next_enemy_id: u32,
+    /// Enemy positions for range queries, rebuilt before towers pick their targets.
+    enemy_grid: SpatialHash,
     pub level: Level,
     pub game_stats: GameStats,
     pub total_time: Duration,
+    pub modifiers: DifficultyModifiers,
+    /// Events since the last `drain_events`.
+    events: Vec<GameEvent>,
+    /// Balance as of the last `MoneyChanged` event.
+    reported_money: u32,
 }
 
 impl GameController {
     pub fn new(settings: &Settings) -> Self {
+        let modifiers = settings.difficulty.modifiers();
+        let first_wave = settings.wave_script.wave(settings, 1);
+        let initial_money = modifiers.scale_money(settings.initial_money);
         GameController {
-            enemies: Vec::new(),
-            towers: Vec::new(),
-            spawn_timer: 0.0,
+            enemies: SlotMap::new(),
+            towers: SlotMap::new(),
+            // The first wave starts right away, one spawn interval in to leave time to build
+            wave_phase: WavePhase::Spawning {
+                spawned: 0,
+                elapsed: -settings.enemy_spawn_interval * modifiers.spawn_interval,
+            },
+            schedule: first_wave.schedule(modifiers.spawn_interval).into_iter().map(|spawn| (1, spawn)).collect(),
+            completed_waves: 0,
+            next_enemy_id: 0,
+            enemy_grid: SpatialHash::new(settings.grid_width, settings.grid_height),
             level: Level::new(settings),
-            game_stats: GameStats::new(settings.initial_money),
+            game_stats: GameStats::new(
+                Economy::new(
+                    initial_money,
+                    EconomyRules::new(settings, &modifiers),
+                ),
+                modifiers.scale_lives(settings.initial_lives),
+                settings.difficulty,
+                settings.mode,
+            ),
             total_time: Duration::from_secs(0),
+            modifiers,
+            events: vec![GameEvent::WaveStarted { wave: 1 }],
+            reported_money: initial_money,
+        }
+    }
+
+    /// Takes the events that happened since the last call. Events pile up until they are
+    /// drained, so whoever runs the game drains them every tick, even just to drop them. The
+    /// combat statistics of `game_stats` are recorded from them here.
+    pub fn drain_events(&mut self) -> Drain<'_, GameEvent> {
+        for event in &self.events {
+            self.game_stats.record_event(event);
+        }
+        self.events.drain(..)
+    }
+
+    /// Reports the balance if it changed since it was last reported.
+    fn report_money(&mut self) {
+        let money = self.game_stats.economy.money();
+        if money != self.reported_money {
+            self.events.push(GameEvent::MoneyChanged {
+                money,
+                change: money as i64 - self.reported_money as i64,
+            });
+            self.reported_money = money;
         }
     }
 
@@ -30,80 +135,553 @@ impl GameController {
         self.total_time += Duration::from_secs_f32(delta_time);
 
         // Update existing enemies
-        for enemy in &mut self.enemies {
+        for enemy in self.enemies.values_mut() {
             enemy.update(&self.level.path, delta_time);
         }
 
         // Tower attacks
-        self.process_tower_attacks();
+        self.process_tower_attacks(delta_time);
 
         // Remove enemies that have reached the end or died
-        self.remove_dead_enemies(settings);
+        self.remove_dead_enemies();
+        self.complete_waves();
 
         // Spawn new enemies
-        self.spawn_timer += delta_time;
-        if self.spawn_timer >= settings.enemy_spawn_interval {
-            self.spawn_timer = 0.0;
-            self.enemies.push(Grunt::new(
-                self.level.start,
-                settings.enemy_health,
-                settings.enemy_speed,
-            ));
+        self.update_waves(settings, delta_time);
+
+        self.report_money();
+
+        // The final balance is always kept, however soon after the previous sample it comes
+        let game_over = self.outcome().is_some();
+        self.game_stats
+            .economy
+            .record_history(self.total_time.as_secs_f32(), game_over);
+    }
+
+    fn update_waves(&mut self, settings: &Settings, delta_time: f32) {
+        match self.wave_phase {
+            WavePhase::Spawning { mut spawned, elapsed } => {
+                let elapsed = elapsed + delta_time;
+                while let Some(&(wave, spawn)) = self.schedule.get(spawned).filter(|(_, s)| s.time <= elapsed) {
+                    self.spawn_enemy(settings, wave, spawn);
+                    spawned += 1;
+                }
+                if spawned < self.schedule.len() {
+                    self.wave_phase = WavePhase::Spawning { spawned, elapsed };
+                } else if self.is_last_wave(settings) {
+                    self.wave_phase = WavePhase::Finished;
+                } else {
+                    self.wave_phase = WavePhase::Break { remaining: settings.wave_break };
+                }
+            }
+            WavePhase::Break { remaining } => {
+                let remaining = remaining - delta_time;
+                if remaining > 0.0 {
+                    self.wave_phase = WavePhase::Break { remaining };
+                } else {
+                    self.start_next_wave(settings);
+                }
+            }
+            WavePhase::Finished => {}
         }
     }
 
-    fn process_tower_attacks(&mut self) {
-        let mut damage_events: Vec<(usize, f32)> = Vec::new();
+    /// Whether the current wave is the one that wins the game. Never true in endless mode.
+    fn is_last_wave(&self, settings: &Settings) -> bool {
+        settings.last_wave().is_some_and(|last| self.game_stats.wave >= last)
+    }
 
-        for tower in &mut self.towers {
-            if tower.can_fire(self.total_time) {
-                let targets = tower.find_targets(&self.enemies);
-                for &target_index in &targets {
-                    damage_events.push((target_index, tower.damage));
+    /// Summary of the enemies that make up the given wave.
+    pub fn wave_composition(&self, settings: &Settings, wave: u32) -> WaveComposition {
+        let definition = settings.wave_script.wave(settings, wave);
+        let groups = &definition.groups;
+        let mut enemies: Vec<(EnemyKind, u32)> = Vec::new();
+        for group in groups {
+            match enemies.iter_mut().find(|(enemy, _)| *enemy == group.enemy) {
+                Some((_, count)) => *count += group.count,
+                None => enemies.push((group.enemy, group.count)),
+            }
+        }
+        WaveComposition {
+            wave,
+            enemies,
+            health: settings.enemy_health
+                * self.modifiers.enemy_health
+                * groups.iter().map(|g| g.health).fold(0.0, f32::max),
+            speed: settings.enemy_speed
+                * self.modifiers.enemy_speed
+                * groups.iter().map(|g| g.speed).fold(0.0, f32::max),
+            bounty: self.game_stats.economy.rules.bounty(wave),
+        }
+    }
+
+    fn spawn_enemy(&mut self, settings: &Settings, wave: u32, spawn: ScheduledSpawn) {
+        let health = settings.enemy_health * self.modifiers.enemy_health * spawn.health;
+        let speed = settings.enemy_speed * self.modifiers.enemy_speed * spawn.speed;
+        let bounty = self.game_stats.economy.rules.bounty(wave);
+        let enemy = Grunt::new(self.next_enemy_id, spawn.enemy, self.level.start, health, speed, wave, bounty);
+        let position = enemy.position;
+        let handle = self.enemies.insert(enemy);
+        self.next_enemy_id += 1;
+        self.events.push(GameEvent::EnemySpawned { enemy: handle, position });
+    }
+
+    /// Starts spawning the next wave from the wave script. While earlier waves are still
+    /// spawning, its enemies join theirs, starting now.
+    fn start_next_wave(&mut self, settings: &Settings) {
+        self.game_stats.next_wave();
+        let wave = self.game_stats.wave;
+        let schedule = settings.wave_script.wave(settings, wave).schedule(self.modifiers.spawn_interval);
+        match self.wave_phase {
+            WavePhase::Spawning { spawned, elapsed } => {
+                self.schedule.drain(..spawned);
+                self.schedule.extend(
+                    schedule
+                        .into_iter()
+                        .map(|spawn| (wave, ScheduledSpawn { time: spawn.time + elapsed, ..spawn })),
+                );
+                // Stable, so enemies due at the same time keep their order
+                self.schedule.sort_by(|a, b| a.1.time.total_cmp(&b.1.time));
+                self.wave_phase = WavePhase::Spawning { spawned: 0, elapsed };
+            }
+            _ => {
+                self.schedule = schedule.into_iter().map(|spawn| (wave, spawn)).collect();
+                self.wave_phase = WavePhase::Spawning { spawned: 0, elapsed: 0.0 };
+            }
+        }
+        self.events.push(GameEvent::WaveStarted { wave });
+    }
+
+    /// The wave that `call_next_wave` would send, or `None` after the last wave.
+    pub fn next_wave_preview(&self, settings: &Settings) -> Option<WaveComposition> {
+        (self.wave_phase != WavePhase::Finished && !self.is_last_wave(settings))
+            .then(|| self.wave_composition(settings, self.game_stats.wave + 1))
+    }
+
+    /// Bonus for calling the next wave now: the early call rate for every second of the
+    /// break that is skipped.
+    pub fn early_call_bonus(&self, settings: &Settings) -> u32 {
+        let skipped = match self.wave_phase {
+            // The waves still spawning do so in full, only the break after them is skipped
+            WavePhase::Spawning { .. } => settings.wave_break,
+            WavePhase::Break { remaining } => remaining,
+            WavePhase::Finished => 0.0,
+        };
+        self.game_stats.economy.rules.early_call_bonus(skipped)
+    }
+
+    /// Sends the next wave right away, alongside any wave still spawning, and pays a bonus for
+    /// the countdown skipped. Returns false when there is no wave to call.
+    pub fn call_next_wave(&mut self, settings: &Settings) -> bool {
+        if self.next_wave_preview(settings).is_none() {
+            return false;
+        }
+        let bonus = self.early_call_bonus(settings);
+        self.game_stats.earn_money(Income::EarlyCall, bonus);
+        self.start_next_wave(settings);
+        self.report_money();
+        true
+    }
+
+    /// Pays out for every wave that has been fully spawned and has no enemies left: interest on
+    /// the banked money first, then the wave bonus and the income of every bank.
+    fn complete_waves(&mut self) {
+        let spawned_waves = match self.wave_phase {
+            // Waves before the earliest one with enemies still due
+            WavePhase::Spawning { spawned, .. } => self.schedule[spawned..]
+                .iter()
+                .map(|&(wave, _)| wave - 1)
+                .min()
+                .unwrap_or(self.game_stats.wave),
+            WavePhase::Break { .. } | WavePhase::Finished => self.game_stats.wave,
+        };
+        while self.completed_waves < spawned_waves {
+            let wave = self.completed_waves + 1;
+            if self.enemies.values().any(|e| e.wave == wave) {
+                break;
+            }
+            self.completed_waves = wave;
+
+            let rules = self.game_stats.economy.rules;
+            let interest = rules.interest(self.game_stats.economy.money());
+            self.game_stats.earn_money_for(wave, Income::Interest, interest);
+            self.game_stats.earn_money_for(wave, Income::WaveBonus, rules.wave_bonus(wave));
+            for tower in self.towers.values().filter(|t| t.tower_type == TowerType::Bank) {
+                self.game_stats.bank_paid(tower.stats_id, wave, rules.bank_income(tower.level));
+            }
+            self.game_stats
+                .economy
+                .record_history(self.total_time.as_secs_f32(), true);
+            self.events.push(GameEvent::WaveCleared { wave });
+        }
+    }
+
+    /// Seconds until the next wave starts spawning, or `None` once the last wave is out.
+    pub fn time_to_next_wave(&self, settings: &Settings) -> Option<f32> {
+        match self.wave_phase {
+            WavePhase::Spawning { elapsed, .. } if !self.is_last_wave(settings) => {
+                let last_spawn = self.schedule.last().map_or(0.0, |(_, spawn)| spawn.time);
+                Some((last_spawn - elapsed).max(0.0) + settings.wave_break)
+            }
+            WavePhase::Break { remaining } => Some(remaining),
+            _ => None,
+        }
+    }
+
+    /// The result of the game once it is decided: defeat when the last life is lost, victory
+    /// once the final wave has been spawned and cleared.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if self.game_stats.lives == 0 {
+            Some(Outcome::Defeat)
+        } else if self.wave_phase == WavePhase::Finished && self.enemies.is_empty() {
+            Some(Outcome::Victory)
+        } else {
+            None
+        }
+    }
+
+    fn process_tower_attacks(&mut self, delta_time: f32) {
+        // Damage is applied once every tower has picked its targets, so all of them see the
+        // enemies as they were at the start of the tick
+        let mut damage_events: Vec<(usize, TowerId, EnemyId, f32)> = Vec::new();
+        self.enemy_grid.rebuild(&self.enemies);
+
+        for (handle, tower) in self.towers.iter_mut() {
+            if !tower.tower_type.attacks() {
+                continue;
+            }
+            // Turrets follow their target while reloading, and hold fire until they face it.
+            // Targets are only looked for when the tower can fire or has lost its target.
+            let can_fire = tower.can_fire(self.total_time);
+            let tracked = tower
+                .tracking
+                .and_then(|target| self.enemies.get(target))
+                .map(|enemy| enemy.position)
+                .filter(|&position| tower.in_range(position));
+            let mut targets = Vec::new();
+            if can_fire || tracked.is_none() {
+                targets = tower.find_targets(&self.enemies, &self.enemy_grid);
+                tower.tracking = targets.first().copied();
+            }
+            let aimed = match tower.tracking.and_then(|target| self.enemies.get(target)) {
+                Some(enemy) => tower.aim_at(enemy.position, delta_time),
+                None => false,
+            };
+            if !can_fire {
+                continue;
+            }
+            if aimed {
+                self.events.push(GameEvent::TowerFired {
+                    tower: handle,
+                    stats_id: tower.stats_id,
+                    tower_type: tower.tower_type,
+                    position: tower.position,
+                    targets: targets.len(),
+                });
+                for &target in &targets {
+                    damage_events.push((tower.stats_id, handle, target, tower.damage));
                 }
                 tower.last_fire_time = self.total_time;
+                tower.last_shot_time = Some(self.total_time);
+            } else if targets.is_empty() {
+                tower.last_fire_time = self.total_time;
             }
         }
 
-        for (target_index, damage) in damage_events {
-            if let Some(enemy) = self.enemies.get_mut(target_index) {
+        for (stats_id, tower, target, damage) in damage_events {
+            if let Some(enemy) = self.enemies.get_mut(target) {
+                let health = enemy.health;
                 enemy.health -= damage;
+                self.events.push(GameEvent::EnemyDamaged {
+                    enemy: target,
+                    tower,
+                    stats_id,
+                    damage,
+                    health,
+                    position: enemy.position,
+                });
             }
         }
     }
 
-    fn remove_dead_enemies(&mut self, settings: &Settings) {
-        self.enemies.retain(|e| {
+    pub fn enemy(&self, handle: EnemyId) -> Option<&Grunt> {
+        self.enemies.get(handle)
+    }
+
+    /// The enemy drawn at a world position given in cells, preferring the one closest to it.
+    pub fn enemy_at(&self, position: (f32, f32), radius: f32) -> Option<EnemyId> {
+        self.enemies
+            .iter()
+            .map(|(handle, e)| (handle, (e.position.0 - position.0).powi(2) + (e.position.1 - position.1).powi(2)))
+            .filter(|&(_, distance)| distance <= radius * radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(handle, _)| handle)
+    }
+
+    fn remove_dead_enemies(&mut self) {
+        self.enemies.retain(|handle, e| {
             if e.health <= 0.0 {
-                self.game_stats.enemy_killed();
-                self.game_stats.add_money(settings.enemy_kill_reward);
+                self.game_stats.earn_money_for(e.wave, Income::Bounty, e.bounty);
+                self.events.push(GameEvent::EnemyKilled {
+                    enemy: handle,
+                    wave: e.wave,
+                    position: e.position,
+                    bounty: e.bounty,
+                });
+                false
+            } else if e.path_index >= self.level.path.len() - 1 {
+                self.game_stats.lives = self.game_stats.lives.saturating_sub(1);
+                self.events.push(GameEvent::EnemyLeaked {
+                    enemy: handle,
+                    wave: e.wave,
+                    position: e.position,
+                    lives: self.game_stats.lives,
+                });
                 false
             } else {
-                e.path_index < self.level.path.len() - 1
+                true
             }
         });
     }
 
     pub fn add_tower(&mut self, position: (usize, usize), tower_type: TowerType) -> bool {
-        if !self.is_position_on_path(position) {
-            let tower_cost = match tower_type {
-                TowerType::Gun => 100,
-                TowerType::Sniper => 150,
-                TowerType::Flame => 200,
-            };
+        if self.check_placement(position, tower_type).is_ok()
+            && self.game_stats.spend_money(tower_type.cost())
+        {
+            let mut tower = Tower::new(position, tower_type);
+            tower.stats_id = self.game_stats.tower_built(tower_type, position, tower_type.cost());
+            let handle = self.towers.insert(tower);
+            self.events.push(GameEvent::TowerBuilt { tower: handle, tower_type, position });
+            self.report_money();
+            true
+        } else {
+            false
+        }
+    }
 
-            if self.game_stats.spend_money(tower_cost) {
-                self.towers.push(Tower::new(position, tower_type));
+    pub fn check_placement(&self, position: (usize, usize), tower_type: TowerType) -> Result<(), PlacementError> {
+        if position.0 >= self.level.width || position.1 >= self.level.height {
+            Err(PlacementError::OutOfBounds)
+        } else if self.is_position_on_path(position) {
+            Err(PlacementError::OnPath)
+        } else if self.tower_at(position).is_some() {
+            Err(PlacementError::Occupied)
+        } else if self.game_stats.economy.money() < tower_type.cost() {
+            Err(PlacementError::Unaffordable)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn tower_at(&self, position: (usize, usize)) -> Option<TowerId> {
+        self.towers.iter().find(|(_, t)| t.position == position).map(|(handle, _)| handle)
+    }
+
+    pub fn upgrade_tower(&mut self, position: (usize, usize)) -> bool {
+        let Some(tower) = self.tower_at(position).and_then(|handle| self.towers.get_mut(handle)) else {
+            return false;
+        };
+        match tower.upgrade_cost() {
+            Some(cost) if self.game_stats.spend_money(cost) => {
+                tower.upgrade();
+                self.game_stats.tower_upgraded(tower.stats_id, cost);
+                self.report_money();
                 true
-            } else {
-                false
             }
-        } else {
-            false
+            _ => false,
+        }
+    }
+
+    pub fn sell_tower(&mut self, position: (usize, usize)) -> bool {
+        match self.tower_at(position) {
+            Some(handle) => {
+                let tower = self.towers.remove(handle).expect("tower_at returns live handles");
+                self.game_stats.tower_sold(tower.stats_id);
+                self.game_stats.earn_money(Income::Refund, tower.sell_value());
+                self.report_money();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_targeting(&mut self, position: (usize, usize), mode: TargetingMode) -> bool {
+        match self.tower_at(position) {
+            Some(handle) => {
+                self.towers[handle].targeting = mode;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cycle_targeting(&mut self, position: (usize, usize)) -> bool {
+        match self.tower_at(position) {
+            Some(handle) => {
+                let tower = &mut self.towers[handle];
+                tower.targeting = tower.targeting.next();
+                true
+            }
+            None => false,
         }
     }
 
     fn is_position_on_path(&self, position: (usize, usize)) -> bool {
         self.level.path.iter().any(|p| p.x == position.0 && p.y == position.1)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enemy_of_wave(game_controller: &GameController, wave: u32, health: f32) -> Grunt {
+        Grunt::new(0, EnemyKind::Grunt, game_controller.level.start, health, 1.0, wave, 10)
+    }
+
+    #[test]
+    fn kills_and_leaks_count_for_the_enemy_wave() {
+        let settings = Settings::new();
+        let mut game_controller = GameController::new(&settings);
+        assert!(game_controller.call_next_wave(&settings));
+        assert_eq!(game_controller.game_stats.wave, 2);
+
+        let killed = enemy_of_wave(&game_controller, 1, 0.0);
+        game_controller.enemies.insert(killed);
+        let mut leaked = enemy_of_wave(&game_controller, 1, 50.0);
+        leaked.path_index = game_controller.level.path.len() - 1;
+        game_controller.enemies.insert(leaked);
+        let earned: Vec<u32> = game_controller.game_stats.waves.iter().map(|w| w.money_earned).collect();
+        game_controller.remove_dead_enemies();
+        game_controller.drain_events();
+
+        // The bounty goes to wave 1 as well
+        let waves = &game_controller.game_stats.waves;
+        assert_eq!((waves[0].kills, waves[0].leaks, waves[0].money_earned), (1, 1, earned[0] + 10));
+        assert_eq!((waves[1].kills, waves[1].leaks, waves[1].money_earned), (0, 0, earned[1]));
+        assert_eq!(game_controller.game_stats.enemies_killed, 1);
+        assert_eq!(game_controller.game_stats.enemies_leaked, 1);
+    }
+
+    /// Runs the game in small steps until `done` holds, killing every enemy as it spawns.
+    fn run_killing(
+        game_controller: &mut GameController,
+        settings: &Settings,
+        mut done: impl FnMut(&GameController) -> bool,
+    ) {
+        for _ in 0..100_000 {
+            if done(game_controller) {
+                return;
+            }
+            game_controller.update(settings, 0.05);
+            for enemy in game_controller.enemies.values_mut() {
+                enemy.health = 0.0;
+            }
+        }
+        panic!("the game never got there");
+    }
+
+    #[test]
+    fn called_wave_spawns_alongside_the_current_one() {
+        let settings = Settings::new();
+        let mut game_controller = GameController::new(&settings);
+        // Into wave 1, with most of it still to come
+        while game_controller.enemies.is_empty() {
+            game_controller.update(&settings, 0.05);
+        }
+        let WavePhase::Spawning { spawned, .. } = game_controller.wave_phase else {
+            panic!("wave 1 is still spawning");
+        };
+        assert!(spawned < game_controller.schedule.len());
+
+        assert!(game_controller.call_next_wave(&settings));
+        game_controller.update(&settings, 0.05);
+
+        assert_eq!(game_controller.game_stats.wave, 2);
+        assert!(game_controller.enemies.values().any(|e| e.wave == 2));
+        let WavePhase::Spawning { spawned, .. } = game_controller.wave_phase else {
+            panic!("both waves are spawning");
+        };
+        assert!(game_controller.schedule[spawned..].iter().any(|&(wave, _)| wave == 1));
+    }
+
+    #[test]
+    fn early_call_bonus_follows_the_countdown() {
+        let settings = Settings::new();
+        let mut game_controller = GameController::new(&settings);
+        let rules = game_controller.game_stats.economy.rules;
+
+        // While spawning, the whole break is skipped
+        assert_eq!(game_controller.early_call_bonus(&settings), rules.early_call_bonus(settings.wave_break));
+
+        game_controller.wave_phase = WavePhase::Break { remaining: 10.0 };
+        let bonus = game_controller.early_call_bonus(&settings);
+        assert_eq!(bonus, rules.early_call_bonus(10.0));
+        game_controller.wave_phase = WavePhase::Break { remaining: 4.0 };
+        assert!(game_controller.early_call_bonus(&settings) < bonus);
+        assert_eq!(game_controller.early_call_bonus(&settings), rules.early_call_bonus(4.0));
+
+        let money = game_controller.game_stats.economy.money();
+        assert!(game_controller.call_next_wave(&settings));
+        assert_eq!(game_controller.game_stats.economy.money(), money + rules.early_call_bonus(4.0));
+        assert_eq!(game_controller.game_stats.economy.earned_from(Income::EarlyCall), rules.early_call_bonus(4.0));
+    }
+
+    #[test]
+    fn overlapping_waves_are_cleared_once_each() {
+        let settings = Settings::new();
+        let mut game_controller = GameController::new(&settings);
+        let rules = game_controller.game_stats.economy.rules;
+        run_killing(&mut game_controller, &settings, |gc| !gc.enemies.is_empty());
+        assert!(game_controller.call_next_wave(&settings));
+        assert!(game_controller.call_next_wave(&settings));
+
+        let mut cleared = Vec::new();
+        run_killing(&mut game_controller, &settings, |gc| gc.completed_waves >= 3);
+        cleared.extend(game_controller.drain_events().filter_map(|event| match event {
+            GameEvent::WaveCleared { wave } => Some(wave),
+            _ => None,
+        }));
+
+        assert_eq!(cleared, vec![1, 2, 3]);
+        assert_eq!(game_controller.game_stats.wave, 3);
+        assert_eq!(
+            game_controller.game_stats.economy.earned_from(Income::WaveBonus),
+            rules.wave_bonus(1) + rules.wave_bonus(2) + rules.wave_bonus(3)
+        );
+    }
+
+    #[test]
+    fn combat_stats_come_from_events() {
+        let settings = Settings::new();
+        let mut game_controller = GameController::new(&settings);
+        let start = game_controller.level.start;
+        let position = (0..game_controller.level.height)
+            .flat_map(|y| (0..game_controller.level.width).map(move |x| (x, y)))
+            .filter(|&cell| game_controller.check_placement(cell, TowerType::Sniper).is_ok())
+            .min_by_key(|&(x, y)| x.abs_diff(start.x) + y.abs_diff(start.y))
+            .unwrap();
+        // No other enemies to shoot at
+        game_controller.wave_phase = WavePhase::Break { remaining: 1000.0 };
+        assert!(game_controller.add_tower(position, TowerType::Sniper));
+        let mut enemy = enemy_of_wave(&game_controller, 1, 1.0);
+        enemy.speed = 0.0;
+        game_controller.enemies.insert(enemy);
+
+        let mut fired = false;
+        for _ in 0..1000 {
+            game_controller.update(&settings, 0.05);
+            if game_controller.events.iter().any(|event| matches!(event, GameEvent::TowerFired { .. })) {
+                fired = true;
+                break;
+            }
+        }
+        assert!(fired);
+        let tower = &game_controller.game_stats.towers[0];
+        assert_eq!((tower.shots_fired, tower.kills), (0, 0));
+
+        game_controller.drain_events();
+        let tower = &game_controller.game_stats.towers[0];
+        assert_eq!((tower.shots_fired, tower.kills), (1, 1));
+        assert_eq!((tower.damage_dealt, tower.overkill), (1.0, Tower::new(position, TowerType::Sniper).damage - 1.0));
+    }
+}
@@ -1,55 +1,64 @@
 use ggez::{Context, GameResult};
 use ggez::graphics::{self, Color};
 use ggez::event::{EventHandler, MouseButton};
-use ggez::timer;
-use ggez::input::mouse::position;
+use ggez::input::keyboard::KeyInput;
 
+use crate::input::KeyBindings;
 use crate::settings::Settings;
-use crate::game_controller::GameController;
-use crate::towers::TowerType;
-use crate::rendering;
+use crate::scenes::{MainMenuScene, SceneStack, Transition};
 
 pub struct GameState {
     settings: Settings,
-    game_controller: GameController,
-    tower_menu_open: bool,
-    tower_menu_position: (usize, usize),
+    key_bindings: KeyBindings,
+    scenes: SceneStack,
 }
 
 impl GameState {
-    pub fn new(settings: Settings) -> GameState {
+    pub fn new(settings: Settings, key_bindings: KeyBindings) -> GameState {
         GameState {
-            game_controller: GameController::new(&settings),
             settings,
-            tower_menu_open: false,
-            tower_menu_position: (0, 0),
+            key_bindings,
+            scenes: SceneStack::new(Box::new(MainMenuScene::new())),
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: Transition) {
+        if !self.scenes.apply(transition) {
+            ctx.request_quit();
         }
     }
 }
 
 impl EventHandler for GameState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
-        let delta_time = timer::delta(ctx).as_secs_f32();
-        self.game_controller.update(&self.settings, delta_time);
+        let transition = match self.scenes.top() {
+            Some(scene) => scene.update(ctx, &self.settings)?,
+            None => Transition::None,
+        };
+        self.apply(ctx, transition);
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         let mut canvas = graphics::Canvas::from_frame(ctx, Color::from([0.1, 0.2, 0.3, 1.0]));
 
-        rendering::render_game(
-            ctx,
-            &mut canvas,
-            &self.game_controller,
-            &self.settings,
-            self.tower_menu_open,
-            self.tower_menu_position,
-        )?;
+        self.scenes.draw(ctx, &mut canvas, &self.settings)?;
 
         canvas.finish(ctx)?;
         Ok(())
     }
 
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        // Minimized windows report a zero size; keep the last layout until the window returns
+        if width < 1.0 || height < 1.0 {
+            return Ok(());
+        }
+        let scale_factor = ctx.gfx.window().scale_factor() as f32;
+        self.settings.resize_window(width, height, scale_factor);
+        self.scenes.resize(&self.settings);
+        Ok(())
+    }
+
     fn mouse_button_down_event(
         &mut self,
         ctx: &mut Context,
@@ -57,31 +66,56 @@ impl EventHandler for GameState {
         x: f32,
         y: f32,
     ) -> GameResult {
-        match button {
-            MouseButton::Right => {
-                let grid_x = (x / self.settings.cell_size) as usize;
-                let grid_y = (y / self.settings.cell_size) as usize;
-                self.tower_menu_open = true;
-                self.tower_menu_position = (grid_x, grid_y);
-            }
-            MouseButton::Left if self.tower_menu_open => {
-                let mouse_pos = position(ctx);
-                if let Some(tower_type) = rendering::tower_menu::get_selected_tower(
-                    mouse_pos.x,
-                    mouse_pos.y,
-                    self.tower_menu_position,
-                    &self.settings,
-                ) {
-                    if self.game_controller.add_tower(self.tower_menu_position, tower_type) {
-                        self.tower_menu_open = false;
-                    }
-                }
-            }
-            MouseButton::Left => {
-                self.tower_menu_open = false;
+        let transition = match self.scenes.top() {
+            Some(scene) => scene.mouse_button_down(ctx, &mut self.settings, button, x, y)?,
+            None => Transition::None,
+        };
+        self.apply(ctx, transition);
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) -> GameResult {
+        match self.scenes.top() {
+            Some(scene) => scene.mouse_button_up(ctx, button),
+            None => Ok(()),
+        }
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) -> GameResult {
+        match self.scenes.top() {
+            Some(scene) => scene.mouse_motion(ctx, &self.settings, (x, y), (dx, dy)),
+            None => Ok(()),
+        }
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) -> GameResult {
+        match self.scenes.top() {
+            Some(scene) => scene.mouse_wheel(ctx, y),
+            None => Ok(()),
+        }
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, repeated: bool) -> GameResult {
+        let Some(action) = input.keycode.and_then(|key| self.key_bindings.action_for(key)) else {
+            return Ok(());
+        };
+        if repeated {
+            return Ok(());
+        }
+        let transition = match self.scenes.top() {
+            Some(scene) => scene.action(ctx, &mut self.settings, action)?,
+            None => Transition::None,
+        };
+        self.apply(ctx, transition);
+        Ok(())
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, input: KeyInput) -> GameResult {
+        if let Some(action) = input.keycode.and_then(|key| self.key_bindings.action_for(key)) {
+            if let Some(scene) = self.scenes.top() {
+                scene.action_released(action);
             }
-            _ => {}
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
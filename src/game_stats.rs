@@ -1,36 +1,294 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::economy::{Economy, Income};
+use crate::events::GameEvent;
+use crate::game_controller::Outcome;
+use crate::settings::{Difficulty, GameMode};
+use crate::towers::TowerType;
+
+/// Points per wave survived in the score; every kill adds one more.
+const SCORE_PER_WAVE: u32 = 100;
+
+#[derive(Clone)]
 pub struct GameStats {
-    pub money: u32,
+    pub economy: Economy,
+    pub lives: u32,
     pub wave: u32,
     pub enemies_killed: u32,
+    pub enemies_leaked: u32,
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    /// Every tower built this game, including sold ones, indexed by `Tower::stats_id`.
+    pub towers: Vec<TowerRecord>,
+    /// One entry per wave reached, starting with wave 1.
+    pub waves: Vec<WaveRecord>,
+}
+
+/// Combat record of a single tower.
+#[derive(Clone, Serialize)]
+pub struct TowerRecord {
+    pub tower_type: TowerType,
+    pub position: (usize, usize),
+    pub built_in_wave: u32,
+    /// Build cost plus every upgrade bought.
+    pub money_spent: u32,
+    /// Times the tower fired at one or more enemies.
+    pub shots_fired: u32,
+    /// Health removed from enemies, not counting damage past zero health.
+    pub damage_dealt: f32,
+    /// Damage past zero health, wasted on enemies that were already dead.
+    pub overkill: f32,
+    pub kills: u32,
+    /// Income paid by banks.
+    pub money_earned: u32,
+    pub sold: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WaveRecord {
+    pub wave: u32,
+    pub kills: u32,
+    pub leaks: u32,
+    /// Money in hand when the wave started.
+    pub money_at_start: u32,
+    /// Money earned for the wave: bounties for its enemies and its payout when cleared, plus
+    /// whatever else came in while it was the newest wave. Refunds from selling are not counted.
+    pub money_earned: u32,
+}
+
+/// Totals over every tower of one type.
+#[derive(Serialize)]
+pub struct TowerTypeSummary {
+    pub tower_type: TowerType,
+    pub towers_built: u32,
+    pub money_spent: u32,
+    pub shots_fired: u32,
+    pub damage_dealt: f32,
+    pub overkill: f32,
+    pub kills: u32,
+    pub money_earned: u32,
+    pub efficiency: f32,
+}
+
+#[derive(Serialize)]
+struct StatsExport<'a> {
+    seed: u64,
+    difficulty: Difficulty,
+    mode: GameMode,
+    outcome: Outcome,
+    score: u32,
+    wave_reached: u32,
+    enemies_killed: u32,
+    enemies_leaked: u32,
+    money_left: u32,
+    money_spent: u32,
+    income: Vec<(Income, u32)>,
+    /// Money over game time as `(seconds, money)` samples.
+    money_history: &'a [(f32, u32)],
+    tower_types: Vec<TowerTypeSummary>,
+    towers: &'a [TowerRecord],
+    waves: &'a [WaveRecord],
+}
+
+impl TowerRecord {
+    /// Damage dealt per unit of money spent on the tower.
+    pub fn efficiency(&self) -> f32 {
+        efficiency(self.damage_dealt, self.money_spent)
+    }
+}
+
+fn efficiency(damage: f32, money: u32) -> f32 {
+    if money > 0 {
+        damage / money as f32
+    } else {
+        0.0
+    }
 }
 
 impl GameStats {
-    pub fn new(initial_money: u32) -> Self {
+    pub fn new(economy: Economy, initial_lives: u32, difficulty: Difficulty, mode: GameMode) -> Self {
+        let waves = vec![WaveRecord::new(1, economy.money())];
         GameStats {
-            money: initial_money,
+            economy,
+            lives: initial_lives,
             wave: 1,
             enemies_killed: 0,
+            enemies_leaked: 0,
+            difficulty,
+            mode,
+            towers: Vec::new(),
+            waves,
         }
     }
 
-    pub fn add_money(&mut self, amount: u32) {
-        self.money += amount;
+    /// Adds money and, unless it is a refund, credits it to the current wave.
+    pub fn earn_money(&mut self, source: Income, amount: u32) {
+        self.earn_money_for(self.wave, source, amount);
     }
 
-    pub fn spend_money(&mut self, amount: u32) -> bool {
-        if self.money >= amount {
-            self.money -= amount;
-            true
-        } else {
-            false
+    /// Adds money and, unless it is a refund, credits it to the given wave.
+    pub fn earn_money_for(&mut self, wave: u32, source: Income, amount: u32) {
+        self.economy.earn(source, amount);
+        if source != Income::Refund {
+            self.wave_record(wave).money_earned += amount;
         }
     }
 
+    pub fn spend_money(&mut self, amount: u32) -> bool {
+        self.economy.spend(amount)
+    }
+
     pub fn next_wave(&mut self) {
         self.wave += 1;
+        self.waves.push(WaveRecord::new(self.wave, self.economy.money()));
+    }
+
+    /// Records the shots, hits, kills and leaks of an event. Money and lives are not touched;
+    /// the controller changes those as the game goes.
+    pub fn record_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::TowerFired { stats_id, .. } => self.towers[stats_id].shots_fired += 1,
+            GameEvent::EnemyDamaged { stats_id, damage, health, .. } => self.hit(stats_id, damage, health),
+            GameEvent::EnemyKilled { wave, .. } => {
+                self.enemies_killed += 1;
+                self.wave_record(wave).kills += 1;
+            }
+            GameEvent::EnemyLeaked { wave, .. } => {
+                self.enemies_leaked += 1;
+                self.wave_record(wave).leaks += 1;
+            }
+            _ => {}
+        }
     }
 
-    pub fn enemy_killed(&mut self) {
-        self.enemies_killed += 1;
+    /// The record of a wave that has been reached, counting from 1.
+    fn wave_record(&mut self, wave: u32) -> &mut WaveRecord {
+        &mut self.waves[wave as usize - 1]
     }
-}
\ No newline at end of file
+
+    /// Starts the record of a newly built tower and returns its id.
+    pub fn tower_built(&mut self, tower_type: TowerType, position: (usize, usize), cost: u32) -> usize {
+        self.towers.push(TowerRecord {
+            tower_type,
+            position,
+            built_in_wave: self.wave,
+            money_spent: cost,
+            shots_fired: 0,
+            damage_dealt: 0.0,
+            overkill: 0.0,
+            kills: 0,
+            money_earned: 0,
+            sold: false,
+        });
+        self.towers.len() - 1
+    }
+
+    pub fn tower_upgraded(&mut self, id: usize, cost: u32) {
+        self.towers[id].money_spent += cost;
+    }
+
+    pub fn tower_sold(&mut self, id: usize) {
+        self.towers[id].sold = true;
+    }
+
+    /// Pays a bank's income for clearing `wave`.
+    pub fn bank_paid(&mut self, id: usize, wave: u32, amount: u32) {
+        self.towers[id].money_earned += amount;
+        self.earn_money_for(wave, Income::Bank, amount);
+    }
+
+    /// Records a hit for `damage` on an enemy that had `health` left. The kill goes to the tower
+    /// whose shot took the enemy to zero health.
+    fn hit(&mut self, id: usize, damage: f32, health: f32) {
+        let record = &mut self.towers[id];
+        let remaining = health.max(0.0);
+        record.damage_dealt += damage.min(remaining);
+        record.overkill += (damage - remaining).max(0.0);
+        if remaining > 0.0 && damage >= remaining {
+            record.kills += 1;
+        }
+    }
+
+    /// Waves survived: every wave reached, except the one the game was lost in.
+    pub fn waves_survived(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Victory => self.wave,
+            Outcome::Defeat => self.wave - 1,
+        }
+    }
+
+    pub fn score(&self, outcome: Outcome) -> u32 {
+        self.waves_survived(outcome) * SCORE_PER_WAVE + self.enemies_killed
+    }
+
+    /// Totals per tower type, in the order the types appear in `TowerType::ALL`.
+    pub fn by_tower_type(&self) -> Vec<TowerTypeSummary> {
+        TowerType::ALL
+            .iter()
+            .map(|&tower_type| {
+                let mut summary = TowerTypeSummary {
+                    tower_type,
+                    towers_built: 0,
+                    money_spent: 0,
+                    shots_fired: 0,
+                    damage_dealt: 0.0,
+                    overkill: 0.0,
+                    kills: 0,
+                    money_earned: 0,
+                    efficiency: 0.0,
+                };
+                for record in self.towers.iter().filter(|r| r.tower_type == tower_type) {
+                    summary.towers_built += 1;
+                    summary.money_spent += record.money_spent;
+                    summary.shots_fired += record.shots_fired;
+                    summary.damage_dealt += record.damage_dealt;
+                    summary.overkill += record.overkill;
+                    summary.kills += record.kills;
+                    summary.money_earned += record.money_earned;
+                }
+                summary.efficiency = efficiency(summary.damage_dealt, summary.money_spent);
+                summary
+            })
+            .collect()
+    }
+
+    /// Writes every statistic of the game to a JSON file.
+    pub fn export_json(&self, path: &Path, seed: u64, outcome: Outcome) -> std::io::Result<()> {
+        let export = StatsExport {
+            seed,
+            difficulty: self.difficulty,
+            mode: self.mode,
+            outcome,
+            score: self.score(outcome),
+            wave_reached: self.wave,
+            enemies_killed: self.enemies_killed,
+            enemies_leaked: self.enemies_leaked,
+            money_left: self.economy.money(),
+            money_spent: self.economy.total_spent(),
+            income: Income::ALL
+                .iter()
+                .map(|&source| (source, self.economy.earned_from(source)))
+                .collect(),
+            money_history: self.economy.history(),
+            tower_types: self.by_tower_type(),
+            towers: &self.towers,
+            waves: &self.waves,
+        };
+        let json = serde_json::to_string_pretty(&export)?;
+        std::fs::write(path, json)
+    }
+}
+
+impl WaveRecord {
+    fn new(wave: u32, money_at_start: u32) -> Self {
+        WaveRecord {
+            wave,
+            kills: 0,
+            leaks: 0,
+            money_at_start,
+            money_earned: 0,
+        }
+    }
+}
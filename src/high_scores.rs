@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_controller::Outcome;
+use crate::game_stats::GameStats;
+use crate::settings::{Difficulty, GameMode};
+
+pub const HIGH_SCORES_PATH: &str = "highscores.json";
+/// Scores kept for each combination of seed, difficulty and mode.
+pub const SCORES_PER_TABLE: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub score: u32,
+    pub waves_survived: u32,
+    pub kills: u32,
+    /// Seconds since the Unix epoch when the game ended.
+    pub timestamp: u64,
+}
+
+impl HighScore {
+    pub fn new(game_stats: &GameStats, outcome: Outcome, seed: u64, timestamp: u64) -> Self {
+        HighScore {
+            seed,
+            difficulty: game_stats.difficulty,
+            mode: game_stats.mode,
+            score: game_stats.score(outcome),
+            waves_survived: game_stats.waves_survived(outcome),
+            kills: game_stats.enemies_killed,
+            timestamp,
+        }
+    }
+
+    fn same_table(&self, other: &HighScore) -> bool {
+        self.seed == other.seed && self.difficulty == other.difficulty && self.mode == other.mode
+    }
+}
+
+/// Every high score table, stored together in one JSON file. Scores are kept best first, so
+/// each table is in order too.
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Reads the high score file. A missing file is an empty table.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut high_scores: HighScores = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|err| format!("{}: {}", path.display(), err))?,
+            Err(err) if err.kind() == ErrorKind::NotFound => HighScores::default(),
+            Err(err) => return Err(format!("could not read {}: {}", path.display(), err)),
+        };
+        // The file may have been edited by hand
+        high_scores
+            .scores
+            .sort_by(|a, b| b.score.cmp(&a.score).then(a.timestamp.cmp(&b.timestamp)));
+        Ok(high_scores)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        fs::write(path, json).map_err(|err| format!("could not write {}: {}", path.display(), err))
+    }
+
+    /// Adds a score to its table and returns its rank, counting from 0, or `None` when it did
+    /// not make the table. A score equal to an older one is ranked below it.
+    pub fn add(&mut self, entry: HighScore) -> Option<usize> {
+        let index = self.scores.partition_point(|other| other.score >= entry.score);
+        let rank = self.scores[..index].iter().filter(|other| other.same_table(&entry)).count();
+        if rank >= SCORES_PER_TABLE {
+            return None;
+        }
+        self.scores.insert(index, entry.clone());
+
+        // Drop the score that fell off the bottom of the table, if any
+        let mut kept = 0;
+        self.scores.retain(|other| {
+            if !other.same_table(&entry) {
+                return true;
+            }
+            kept += 1;
+            kept <= SCORES_PER_TABLE
+        });
+        Some(rank)
+    }
+
+    /// The table `entry` belongs to, best score first.
+    pub fn table<'a>(&'a self, entry: &'a HighScore) -> impl Iterator<Item = &'a HighScore> + 'a {
+        self.scores.iter().filter(move |other| other.same_table(entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seed: u64, score: u32, timestamp: u64) -> HighScore {
+        HighScore {
+            seed,
+            difficulty: Difficulty::Normal,
+            mode: GameMode::Classic,
+            score,
+            waves_survived: 0,
+            kills: 0,
+            timestamp,
+        }
+    }
+
+    fn scores(high_scores: &HighScores, seed: u64) -> Vec<(u32, u64)> {
+        high_scores
+            .table(&entry(seed, 0, 0))
+            .map(|score| (score.score, score.timestamp))
+            .collect()
+    }
+
+    #[test]
+    fn ranks_within_the_table() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.add(entry(1, 100, 1)), Some(0));
+        assert_eq!(high_scores.add(entry(1, 300, 2)), Some(0));
+        // Another seed is another table, whatever its score
+        assert_eq!(high_scores.add(entry(2, 200, 3)), Some(0));
+        assert_eq!(high_scores.add(entry(1, 200, 4)), Some(1));
+        assert_eq!(scores(&high_scores, 1), vec![(300, 2), (200, 4), (100, 1)]);
+        assert_eq!(scores(&high_scores, 2), vec![(200, 3)]);
+    }
+
+    #[test]
+    fn ties_rank_below_older_scores() {
+        let mut high_scores = HighScores::default();
+        high_scores.add(entry(1, 100, 1));
+        high_scores.add(entry(1, 200, 2));
+        assert_eq!(high_scores.add(entry(1, 100, 3)), Some(2));
+        assert_eq!(high_scores.add(entry(1, 200, 4)), Some(1));
+        assert_eq!(scores(&high_scores, 1), vec![(200, 2), (200, 4), (100, 1), (100, 3)]);
+    }
+
+    #[test]
+    fn keeps_the_best_of_each_table() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=SCORES_PER_TABLE as u32 {
+            high_scores.add(entry(1, score * 10, score as u64));
+        }
+        high_scores.add(entry(2, 5, 0));
+
+        // As low as the worst is not enough, better pushes the worst out
+        assert_eq!(high_scores.add(entry(1, 10, 100)), None);
+        assert_eq!(high_scores.add(entry(1, 15, 101)), Some(SCORES_PER_TABLE - 1));
+        let table = scores(&high_scores, 1);
+        assert_eq!(table.len(), SCORES_PER_TABLE);
+        assert_eq!(table.last(), Some(&(15, 101)));
+        assert!(!table.contains(&(10, 1)));
+        assert_eq!(scores(&high_scores, 2), vec![(5, 0)]);
+    }
+}
@@ -0,0 +1,112 @@
+use ggez::input::keyboard::KeyCode;
+
+/// Parses a key name as written in the keybindings file, e.g. "T", "1", "Space" or "F5".
+/// Names are case-insensitive.
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let name = name.trim().to_ascii_lowercase();
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some(key) = parse_char(c) {
+            return Some(key);
+        }
+    }
+
+    let key = match name.as_str() {
+        "escape" | "esc" => KeyCode::Escape,
+        "space" => KeyCode::Space,
+        "enter" | "return" => KeyCode::Return,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Back,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "plus" => KeyCode::Plus,
+        "minus" => KeyCode::Minus,
+        "f1" => KeyCode::F1,
+        "f2" => KeyCode::F2,
+        "f3" => KeyCode::F3,
+        "f4" => KeyCode::F4,
+        "f5" => KeyCode::F5,
+        "f6" => KeyCode::F6,
+        "f7" => KeyCode::F7,
+        "f8" => KeyCode::F8,
+        "f9" => KeyCode::F9,
+        "f10" => KeyCode::F10,
+        "f11" => KeyCode::F11,
+        "f12" => KeyCode::F12,
+        "numpad0" => KeyCode::Numpad0,
+        "numpad1" => KeyCode::Numpad1,
+        "numpad2" => KeyCode::Numpad2,
+        "numpad3" => KeyCode::Numpad3,
+        "numpad4" => KeyCode::Numpad4,
+        "numpad5" => KeyCode::Numpad5,
+        "numpad6" => KeyCode::Numpad6,
+        "numpad7" => KeyCode::Numpad7,
+        "numpad8" => KeyCode::Numpad8,
+        "numpad9" => KeyCode::Numpad9,
+        "numpadadd" => KeyCode::NumpadAdd,
+        "numpadsubtract" => KeyCode::NumpadSubtract,
+        _ => return None,
+    };
+    Some(key)
+}
+
+fn parse_char(c: char) -> Option<KeyCode> {
+    let key = match c {
+        'a' => KeyCode::A,
+        'b' => KeyCode::B,
+        'c' => KeyCode::C,
+        'd' => KeyCode::D,
+        'e' => KeyCode::E,
+        'f' => KeyCode::F,
+        'g' => KeyCode::G,
+        'h' => KeyCode::H,
+        'i' => KeyCode::I,
+        'j' => KeyCode::J,
+        'k' => KeyCode::K,
+        'l' => KeyCode::L,
+        'm' => KeyCode::M,
+        'n' => KeyCode::N,
+        'o' => KeyCode::O,
+        'p' => KeyCode::P,
+        'q' => KeyCode::Q,
+        'r' => KeyCode::R,
+        's' => KeyCode::S,
+        't' => KeyCode::T,
+        'u' => KeyCode::U,
+        'v' => KeyCode::V,
+        'w' => KeyCode::W,
+        'x' => KeyCode::X,
+        'y' => KeyCode::Y,
+        'z' => KeyCode::Z,
+        '0' => KeyCode::Key0,
+        '1' => KeyCode::Key1,
+        '2' => KeyCode::Key2,
+        '3' => KeyCode::Key3,
+        '4' => KeyCode::Key4,
+        '5' => KeyCode::Key5,
+        '6' => KeyCode::Key6,
+        '7' => KeyCode::Key7,
+        '8' => KeyCode::Key8,
+        '9' => KeyCode::Key9,
+        '+' => KeyCode::Plus,
+        '-' => KeyCode::Minus,
+        '=' => KeyCode::Equals,
+        ',' => KeyCode::Comma,
+        '.' => KeyCode::Period,
+        '/' => KeyCode::Slash,
+        ';' => KeyCode::Semicolon,
+        '[' => KeyCode::LBracket,
+        ']' => KeyCode::RBracket,
+        '`' => KeyCode::Grave,
+        _ => return None,
+    };
+    Some(key)
+}
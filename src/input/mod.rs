@@ -0,0 +1,152 @@
+mod keys;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ggez::input::keyboard::KeyCode;
+use serde::Deserialize;
+
+use crate::settings::{read_toml_file, SettingsError};
+
+use keys::parse_key;
+
+pub const DEFAULT_KEYBINDINGS_PATH: &str = "keybindings.toml";
+
+/// Everything the player can do from the keyboard. Scenes react to actions, never to raw keys.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    BuildGun,
+    BuildSniper,
+    BuildFlame,
+    BuildBank,
+    Upgrade,
+    Sell,
+    CycleTargeting,
+    Pause,
+    Speed,
+    CallWave,
+    ToggleFrameStats,
+    ToggleHeatmap,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    Cancel,
+    Confirm,
+}
+
+impl Action {
+    const ALL: [Action; 18] = [
+        Action::BuildGun,
+        Action::BuildSniper,
+        Action::BuildFlame,
+        Action::BuildBank,
+        Action::Upgrade,
+        Action::Sell,
+        Action::CycleTargeting,
+        Action::Pause,
+        Action::Speed,
+        Action::CallWave,
+        Action::ToggleFrameStats,
+        Action::ToggleHeatmap,
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::Cancel,
+        Action::Confirm,
+    ];
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::BuildGun => &[KeyCode::Key1],
+            Action::BuildSniper => &[KeyCode::Key2],
+            Action::BuildFlame => &[KeyCode::Key3],
+            Action::BuildBank => &[KeyCode::Key4],
+            Action::Upgrade => &[KeyCode::U],
+            Action::Sell => &[KeyCode::X],
+            Action::CycleTargeting => &[KeyCode::T],
+            Action::Pause => &[KeyCode::P, KeyCode::Space],
+            Action::Speed => &[KeyCode::F],
+            Action::CallWave => &[KeyCode::N],
+            Action::ToggleFrameStats => &[KeyCode::F3],
+            Action::ToggleHeatmap => &[KeyCode::H],
+            Action::PanUp => &[KeyCode::W, KeyCode::Up],
+            Action::PanDown => &[KeyCode::S, KeyCode::Down],
+            Action::PanLeft => &[KeyCode::A, KeyCode::Left],
+            Action::PanRight => &[KeyCode::D, KeyCode::Right],
+            Action::Cancel => &[KeyCode::Escape],
+            Action::Confirm => &[KeyCode::Return],
+        }
+    }
+}
+
+/// A binding in the keybindings file: either a single key name or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+pub struct KeyBindings {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        let mut bindings = HashMap::new();
+        for action in Action::ALL {
+            for &key in action.default_keys() {
+                bindings.insert(key, action);
+            }
+        }
+        KeyBindings { bindings }
+    }
+
+    /// Loads the defaults, replacing the keys of every action listed in the keybindings file.
+    /// Without an explicit path, `keybindings.toml` is used if it exists.
+    pub fn load(path: Option<&Path>) -> Result<Self, SettingsError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => (PathBuf::from(DEFAULT_KEYBINDINGS_PATH), false),
+        };
+        let Some(file) = read_toml_file::<HashMap<Action, KeyList>>(&path, required)? else {
+            return Ok(KeyBindings::new());
+        };
+
+        let mut key_bindings = KeyBindings::new();
+        for action in file.keys() {
+            key_bindings.bindings.retain(|_, bound| bound != action);
+        }
+        for (&action, keys) in &file {
+            let names = match keys {
+                KeyList::One(name) => std::slice::from_ref(name),
+                KeyList::Many(names) => names.as_slice(),
+            };
+            for name in names {
+                let key = parse_key(name).ok_or_else(|| SettingsError::Parse {
+                    path: path.clone(),
+                    message: format!("unknown key '{}' for {:?}", name, action),
+                })?;
+                if let Some(previous) = key_bindings.bindings.insert(key, action) {
+                    if previous != action {
+                        return Err(SettingsError::Parse {
+                            path: path.clone(),
+                            message: format!(
+                                "key '{}' is bound to both {:?} and {:?}",
+                                name, previous, action
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(key_bindings)
+    }
+
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+}
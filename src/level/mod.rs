@@ -1,6 +1,6 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::settings::Settings;
-use ggez::graphics::Color;
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct Point {
@@ -19,8 +19,9 @@ pub struct Level {
 
 impl Level {
     pub fn new(settings: &Settings) -> Self {
+        // Seeding from the settings makes a given seed always produce the same map.
+        let mut rng = StdRng::seed_from_u64(settings.seed);
         loop {
-            let mut rng = rand::thread_rng();
             let start = Point {
                 x: rng.gen_range(0..settings.grid_width),
                 y: rng.gen_range(0..settings.grid_height),
@@ -98,37 +99,4 @@ impl Level {
         }
         true
     }
-
-    pub fn get_path_colors(&self) -> Vec<(Point, Color)> {
-        let total_points = 2 + self.waypoints.len(); // start, waypoints, and end
-        let mut colors = vec![];
-
-        colors.push((self.start, Color::RED));
-
-        for (i, &waypoint) in self.waypoints.iter().enumerate() {
-            let hue = (i + 1) as f32 / total_points as f32;
-            colors.push((waypoint, hsv_to_rgb(hue, 1.0, 1.0)));
-        }
-
-        colors.push((self.end, Color::BLUE));
-
-        colors
-    }
 }
-
-fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Color {
-    let c = v * s;
-    let x = c * (1.0 - ((h * 6.0) % 2.0 - 1.0).abs());
-    let m = v - c;
-
-    let (r, g, b) = match (h * 6.0).floor() as i32 {
-        0 => (c, x, 0.0),
-        1 => (x, c, 0.0),
-        2 => (0.0, c, x),
-        3 => (0.0, x, c),
-        4 => (x, 0.0, c),
-        _ => (c, 0.0, x),
-    };
-
-    Color::new((r + m) as f32, (g + m) as f32, (b + m) as f32, 1.0)
-}
\ No newline at end of file
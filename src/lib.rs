@@ -0,0 +1,17 @@
+//! Rules and simulation of the game, shared by the game itself and the `td-sim` balance tool.
+//! Nothing in here needs a window.
+
+pub mod analysis;
+pub mod bot;
+pub mod economy;
+pub mod entities;
+pub mod events;
+pub mod game_controller;
+pub mod game_stats;
+pub mod high_scores;
+pub mod level;
+pub mod settings;
+pub mod slot_map;
+pub mod spatial;
+pub mod towers;
+pub mod waves;
@@ -1,25 +1,74 @@
 use ggez::{ContextBuilder, event};
+use crate::game_controller::Outcome;
 use crate::game_state::GameState;
-use crate::settings::Settings;
-use crate::towers::TowerType;
+use crate::input::KeyBindings;
+use crate::settings::{Settings, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH};
 
+mod camera;
 mod rendering;
-mod entities;
-mod towers;
-mod level;
-mod settings;
-mod game_controller;
 mod game_state;
-mod game_stats;
+mod input;
+mod scenes;
 mod ui;
 
+// The game's own modules refer to these through `crate::`
+use tower_defense::{
+    analysis, bot, economy, entities, events, game_controller, game_stats, high_scores, level, settings, slot_map, towers,
+    waves,
+};
+
 fn main() -> ggez::GameResult {
-    let settings = Settings::new();
-    let (ctx, event_loop) = ContextBuilder::new("tower_defense", "Your Name")
+    let mut settings = Settings::load(std::env::args().skip(1)).unwrap_or_else(|err| exit_with_error(err));
+    if let Some(kind) = settings.bot.filter(|_| settings.headless) {
+        let (outcome, game_stats) = bot::run_headless(&settings, kind, bot::DEFAULT_MAX_WAVES);
+        let result = match outcome {
+            Some(Outcome::Victory) => "victory",
+            Some(Outcome::Defeat) => "defeat",
+            None => "capped",
+        };
+        println!(
+            "{} bot, seed {}, {} {}: {} at wave {}, score {}, {} kills, {} leaks, ${} left",
+            kind,
+            settings.seed,
+            settings.difficulty,
+            settings.mode,
+            result,
+            game_stats.wave,
+            // A capped game is scored for the waves it got through, like a lost one
+            game_stats.score(outcome.unwrap_or(Outcome::Defeat)),
+            game_stats.enemies_killed,
+            game_stats.enemies_leaked,
+            game_stats.economy.money()
+        );
+        return Ok(());
+    }
+    let key_bindings = KeyBindings::load(settings.keybindings_path.as_deref()).unwrap_or_else(|err| exit_with_error(err));
+    // Themes are looked up in ./resources as well as next to the executable
+    let (mut ctx, event_loop) = ContextBuilder::new("tower_defense", "Your Name")
+        .add_resource_path("resources")
         .window_setup(ggez::conf::WindowSetup::default().title("Tower Defense"))
-        .window_mode(ggez::conf::WindowMode::default().dimensions(settings.window_width, settings.window_height))
+        .window_mode(
+            ggez::conf::WindowMode::default()
+                .dimensions(settings.window_width, settings.window_height)
+                .min_dimensions(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT)
+                .resizable(true),
+        )
         .build()?;
 
-    let state = GameState::new(settings);
+    let scale_factor = ctx.gfx.window().scale_factor() as f32;
+    if let Some(monitor) = ctx.gfx.window().current_monitor() {
+        let screen = monitor.size();
+        let (width, height) = settings.initial_window_size(screen.width as f32, screen.height as f32, scale_factor);
+        ctx.gfx.set_drawable_size(width, height)?;
+    }
+    let (width, height) = ctx.gfx.drawable_size();
+    settings.resize_window(width, height, scale_factor);
+
+    let state = GameState::new(settings, key_bindings);
     event::run(ctx, event_loop, state)
-}
\ No newline at end of file
+}
+
+fn exit_with_error(err: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(2);
+}
@@ -0,0 +1,212 @@
+use std::f32::consts::TAU;
+
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, MeshBuilder, Text, TextFragment, TextLayout};
+use ggez::mint::Point2;
+use rand::Rng;
+
+use crate::events::GameEvent;
+use crate::settings::Settings;
+use crate::slot_map::SlotMap;
+use crate::towers::{Tower, TowerType};
+
+/// Live effects at most. Past it new effects are dropped, so a huge fight costs no more to
+/// draw than a busy one.
+const EFFECT_BUDGET: usize = 600;
+/// Floating numbers at most, since each is a text draw of its own.
+const NUMBER_BUDGET: usize = 40;
+
+const SPARKS_PER_HIT: usize = 3;
+const SPARKS_PER_DEATH: usize = 12;
+/// Cells per second at which floating numbers rise.
+const NUMBER_RISE: f32 = 0.8;
+
+const FLASH_COLOR: Color = Color::new(1.0, 0.9, 0.5, 0.9);
+const GUN_TRACER_COLOR: Color = Color::new(1.0, 0.9, 0.3, 0.8);
+const SNIPER_TRACER_COLOR: Color = Color::new(0.9, 0.95, 1.0, 0.9);
+const FLAME_COLOR: Color = Color::new(1.0, 0.45, 0.1, 0.5);
+const SPARK_COLOR: Color = Color::new(1.0, 0.8, 0.4, 1.0);
+const DEATH_COLOR: Color = Color::new(1.0, 0.3, 0.1, 1.0);
+const DAMAGE_COLOR: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+const BOUNTY_COLOR: Color = Color::new(1.0, 0.85, 0.2, 1.0);
+
+/// Positions and sizes are in cells.
+enum Shape {
+    Flash { center: (f32, f32), radius: f32 },
+    Tracer { from: (f32, f32), to: (f32, f32), width: f32 },
+    FlameCone { from: (f32, f32), to: (f32, f32) },
+    Particle { position: (f32, f32), velocity: (f32, f32), radius: f32 },
+    Number { position: (f32, f32), text: String },
+}
+
+struct Effect {
+    shape: Shape,
+    color: Color,
+    /// Seconds since the effect started; it fades out until `lifetime`.
+    age: f32,
+    lifetime: f32,
+}
+
+impl Effect {
+    fn faded_color(&self) -> Color {
+        let mut color = self.color;
+        color.a *= 1.0 - self.age / self.lifetime;
+        color
+    }
+}
+
+/// Short-lived combat visuals, started from game events and drawn over the enemies and
+/// towers.
+pub struct Effects {
+    effects: Vec<Effect>,
+    numbers: usize,
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        Effects { effects: Vec::new(), numbers: 0 }
+    }
+
+    /// Starts the effects of an event. `towers` is used to find where shots come from.
+    pub fn handle(&mut self, event: &GameEvent, towers: &SlotMap<Tower>) {
+        match *event {
+            GameEvent::TowerFired { tower_type, position, .. } if tower_type != TowerType::Flame => {
+                self.add(Shape::Flash { center: cell_center(position), radius: 0.3 }, FLASH_COLOR, 0.08);
+            }
+            GameEvent::EnemyDamaged { tower, damage, position, .. } => {
+                if let Some(tower) = towers.get(tower) {
+                    let from = cell_center(tower.position);
+                    match tower.tower_type {
+                        TowerType::Flame => self.add(Shape::FlameCone { from, to: position }, FLAME_COLOR, 0.2),
+                        TowerType::Sniper => {
+                            self.add(Shape::Tracer { from, to: position, width: 0.06 }, SNIPER_TRACER_COLOR, 0.15)
+                        }
+                        _ => self.add(Shape::Tracer { from, to: position, width: 0.04 }, GUN_TRACER_COLOR, 0.1),
+                    }
+                }
+                self.add_sparks(position, SPARKS_PER_HIT, 2.0, SPARK_COLOR, 0.25);
+                self.add_number(position, format!("-{:.0}", damage), DAMAGE_COLOR, 0.8);
+            }
+            GameEvent::EnemyKilled { position, bounty, .. } => {
+                self.add_sparks(position, SPARKS_PER_DEATH, 3.0, DEATH_COLOR, 0.5);
+                self.add_number(position, format!("+${}", bounty), BOUNTY_COLOR, 1.2);
+            }
+            _ => {}
+        }
+    }
+
+    fn add(&mut self, shape: Shape, color: Color, lifetime: f32) {
+        if self.effects.len() < EFFECT_BUDGET {
+            self.effects.push(Effect { shape, color, age: 0.0, lifetime });
+        }
+    }
+
+    fn add_sparks(&mut self, position: (f32, f32), count: usize, speed: f32, color: Color, lifetime: f32) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..TAU);
+            let speed = speed * rng.gen_range(0.5..1.0);
+            let velocity = (angle.cos() * speed, angle.sin() * speed);
+            self.add(Shape::Particle { position, velocity, radius: 0.06 }, color, lifetime);
+        }
+    }
+
+    fn add_number(&mut self, position: (f32, f32), text: String, color: Color, lifetime: f32) {
+        if self.numbers < NUMBER_BUDGET && self.effects.len() < EFFECT_BUDGET {
+            self.numbers += 1;
+            self.add(Shape::Number { position, text }, color, lifetime);
+        }
+    }
+
+    /// Advances every effect by `delta_time` seconds of game time and drops finished ones.
+    pub fn update(&mut self, delta_time: f32) {
+        for effect in &mut self.effects {
+            effect.age += delta_time;
+            match &mut effect.shape {
+                Shape::Particle { position, velocity, .. } => {
+                    position.0 += velocity.0 * delta_time;
+                    position.1 += velocity.1 * delta_time;
+                }
+                Shape::Number { position, .. } => position.1 -= NUMBER_RISE * delta_time,
+                _ => {}
+            }
+        }
+        self.effects.retain(|effect| effect.age < effect.lifetime);
+        self.numbers = self.effects.iter().filter(|e| matches!(e.shape, Shape::Number { .. })).count();
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+        self.numbers = 0;
+    }
+
+    /// Draws every effect in world coordinates: the shapes as one mesh, then the numbers.
+    pub fn render(&self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        let to_pixels = |(x, y): (f32, f32)| Point2 { x: x * settings.cell_size, y: y * settings.cell_size };
+
+        let mut builder = MeshBuilder::new();
+        let mut empty = true;
+        for effect in &self.effects {
+            let color = effect.faded_color();
+            match effect.shape {
+                Shape::Flash { center, radius } => {
+                    builder.circle(DrawMode::fill(), to_pixels(center), radius * settings.cell_size, 0.5, color)?;
+                }
+                Shape::Tracer { from, to, width } => {
+                    if distance(from, to) < 0.01 {
+                        continue;
+                    }
+                    builder.line(&[to_pixels(from), to_pixels(to)], width * settings.cell_size, color)?;
+                }
+                Shape::FlameCone { from, to } => {
+                    let length = distance(from, to);
+                    if length < 0.01 {
+                        continue;
+                    }
+                    // Perpendicular to the cone, a quarter of its length: the cone is half as wide
+                    // as it is long at the target
+                    let side = ((from.1 - to.1) * 0.25, (to.0 - from.0) * 0.25);
+                    builder.polygon(
+                        DrawMode::fill(),
+                        &[
+                            to_pixels(from),
+                            to_pixels((to.0 + side.0, to.1 + side.1)),
+                            to_pixels((to.0 - side.0, to.1 - side.1)),
+                        ],
+                        color,
+                    )?;
+                }
+                Shape::Particle { position, radius, .. } => {
+                    builder.circle(DrawMode::fill(), to_pixels(position), radius * settings.cell_size, 0.5, color)?;
+                }
+                Shape::Number { .. } => continue,
+            }
+            empty = false;
+        }
+        if !empty {
+            canvas.draw(&Mesh::from_data(ctx, builder.build()), graphics::DrawParam::default());
+        }
+
+        for effect in &self.effects {
+            if let Shape::Number { position, ref text } = effect.shape {
+                let mut text = Text::new(TextFragment::new(text.as_str()).scale(0.4 * settings.cell_size));
+                text.set_layout(TextLayout::center());
+                canvas.draw(
+                    &text,
+                    graphics::DrawParam::default()
+                        .dest(to_pixels(position))
+                        .color(effect.faded_color()),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn cell_center(position: (usize, usize)) -> (f32, f32) {
+    (position.0 as f32 + 0.5, position.1 as f32 + 0.5)
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
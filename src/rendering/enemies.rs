@@ -1,57 +1,77 @@
-use ggez::{Context, GameResult};
-use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect};
-use ggez::mint::Point2;
+use ggez::graphics::{Color, DrawParam, InstanceArray, Rect};
 
 use crate::game_controller::GameController;
 use crate::settings::Settings;
+use crate::waves::EnemyKind;
 
-pub fn render_enemies(
-    ctx: &mut Context,
-    canvas: &mut Canvas,
+use super::theme::{SpriteKey, Theme};
+
+/// Seconds added to the animation time per enemy id, so enemies do not all step in time.
+const ANIMATION_PHASE: f32 = 0.13;
+
+/// Color of an enemy drawn without a sprite.
+fn body_color(kind: EnemyKind) -> Color {
+    match kind {
+        EnemyKind::Grunt => Color::YELLOW,
+        EnemyKind::Runner => Color::new(0.3, 0.9, 1.0, 1.0),
+        EnemyKind::Brute => Color::new(0.9, 0.45, 0.15, 1.0),
+    }
+}
+
+/// Fills the per-frame instance arrays for enemies. `bodies` is drawn with a unit circle mesh
+/// for enemies the theme has no sprite for, `sprites` with the theme's sheet for the others,
+/// and `health_bars` as plain quads.
+pub fn fill_enemy_instances(
+    bodies: &mut InstanceArray,
+    sprites: &mut InstanceArray,
+    health_bars: &mut InstanceArray,
     game_controller: &GameController,
     settings: &Settings,
-) -> GameResult {
-    for enemy in &game_controller.enemies {
-        let enemy_circle = Mesh::new_circle(
-            ctx,
-            DrawMode::fill(),
-            Point2 {
-                x: enemy.position.0 * settings.cell_size,
-                y: enemy.position.1 * settings.cell_size,
-            },
-            settings.enemy_radius,
-            0.1,
-            Color::YELLOW,
-        )?;
-        canvas.draw(&enemy_circle, graphics::DrawParam::default());
-
-        // Draw health bar background
-        let health_bar_bg = Mesh::new_rectangle(
-            ctx,
-            DrawMode::fill(),
-            Rect::new(
-                enemy.health_bar.position.0 * settings.cell_size,
-                enemy.health_bar.position.1 * settings.cell_size,
-                enemy.health_bar.width * settings.cell_size,
-                enemy.health_bar.height * settings.cell_size,
+    theme: Option<&Theme>,
+) {
+    let time = game_controller.total_time.as_secs_f32();
+    bodies.clear();
+    sprites.clear();
+    for enemy in game_controller.enemies.values() {
+        match theme.and_then(|theme| theme.sprite(SpriteKey::Enemy(enemy.kind)).map(|s| (theme, s))) {
+            Some((theme, sprite)) => {
+                let area = Rect::new(
+                    enemy.position.0 * settings.cell_size - settings.enemy_radius,
+                    enemy.position.1 * settings.cell_size - settings.enemy_radius,
+                    settings.enemy_radius * 2.0,
+                    settings.enemy_radius * 2.0,
+                );
+                sprites.push(theme.draw_param(sprite.frame(time + enemy.id as f32 * ANIMATION_PHASE), area));
+            }
+            None => bodies.push(
+                DrawParam::default()
+                    .dest([
+                        enemy.position.0 * settings.cell_size,
+                        enemy.position.1 * settings.cell_size,
+                    ])
+                    .scale([settings.enemy_radius, settings.enemy_radius])
+                    .color(body_color(enemy.kind)),
             ),
-            Color::RED,
-        )?;
-        canvas.draw(&health_bar_bg, graphics::DrawParam::default());
+        }
+    }
 
-        // Draw health bar fill
-        let health_bar_fill = Mesh::new_rectangle(
-            ctx,
-            DrawMode::fill(),
-            Rect::new(
-                enemy.health_bar.position.0 * settings.cell_size,
-                enemy.health_bar.position.1 * settings.cell_size,
-                enemy.health_bar.get_fill_width() * settings.cell_size,
-                enemy.health_bar.height * settings.cell_size,
-            ),
-            Color::GREEN,
-        )?;
-        canvas.draw(&health_bar_fill, graphics::DrawParam::default());
+    health_bars.clear();
+    for enemy in game_controller.enemies.values() {
+        let bar = &enemy.health_bar;
+        let origin = [bar.position.0 * settings.cell_size, bar.position.1 * settings.cell_size];
+
+        // Background, then the fill on top of it
+        health_bars.push(
+            DrawParam::default()
+                .dest(origin)
+                .scale([bar.width * settings.cell_size, bar.height * settings.cell_size])
+                .color(Color::RED),
+        );
+        health_bars.push(
+            DrawParam::default()
+                .dest(origin)
+                .scale([bar.get_fill_width() * settings.cell_size, bar.height * settings.cell_size])
+                .color(Color::GREEN),
+        );
     }
-    Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, Text, TextFragment};
+
+/// Number of frames the averages are taken over.
+const SAMPLE_COUNT: usize = 120;
+
+/// Rolling averages of the frame time and of the time spent building the frame's draw calls.
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    draw_times: VecDeque<Duration>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            frame_times: VecDeque::with_capacity(SAMPLE_COUNT),
+            draw_times: VecDeque::with_capacity(SAMPLE_COUNT),
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration, draw_time: Duration) {
+        if self.frame_times.len() == SAMPLE_COUNT {
+            self.frame_times.pop_front();
+            self.draw_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.draw_times.push_back(draw_time);
+    }
+
+    fn average_ms(samples: &VecDeque<Duration>) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        samples.iter().sum::<Duration>().as_secs_f32() * 1000.0 / samples.len() as f32
+    }
+
+    pub fn render(&self, ctx: &mut Context, canvas: &mut Canvas, x: f32, y: f32, scale: f32) -> GameResult {
+        let frame_ms = Self::average_ms(&self.frame_times);
+        let fps = if frame_ms > 0.0 { 1000.0 / frame_ms } else { 0.0 };
+        let text = Text::new(
+            TextFragment::new(format!(
+                "frame {:.2} ms ({:.0} fps)  draw {:.2} ms",
+                frame_ms,
+                fps,
+                Self::average_ms(&self.draw_times)
+            ))
+            .scale(18.0 * scale),
+        );
+        let dims = text.measure(ctx)?;
+        canvas.draw(
+            &text,
+            graphics::DrawParam::default()
+                .dest([x - dims.x, y])
+                .color(Color::WHITE),
+        );
+        Ok(())
+    }
+}
@@ -1,33 +1,22 @@
-use ggez::{Context, GameResult};
-use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect};
+use ggez::GameResult;
+use ggez::graphics::{Color, MeshBuilder};
+use ggez::mint::Point2;
 
-use crate::game_controller::GameController;
+use crate::level::Level;
 use crate::settings::Settings;
 
-pub fn render_grid(
-    ctx: &mut Context,
-    canvas: &mut Canvas,
-    game_controller: &GameController,
-    settings: &Settings,
-) -> GameResult {
-    for y in 0..game_controller.level.height {
-        for x in 0..game_controller.level.width {
-            let rect = Rect::new(
-                x as f32 * settings.cell_size,
-                y as f32 * settings.cell_size,
-                settings.cell_size,
-                settings.cell_size,
-            );
+/// Adds the grid lines of a level to a mesh. Built once per level, not every frame.
+pub fn build_grid(builder: &mut MeshBuilder, level: &Level, settings: &Settings) -> GameResult {
+    let width = level.width as f32 * settings.cell_size;
+    let height = level.height as f32 * settings.cell_size;
 
-            let mesh = Mesh::new_rectangle(
-                ctx,
-                DrawMode::stroke(1.0),
-                rect,
-                Color::WHITE,
-            )?;
-
-            canvas.draw(&mesh, graphics::DrawParam::default());
-        }
+    for x in 0..=level.width {
+        let x = x as f32 * settings.cell_size;
+        builder.line(&[Point2 { x, y: 0.0 }, Point2 { x, y: height }], 1.0, Color::WHITE)?;
+    }
+    for y in 0..=level.height {
+        let y = y as f32 * settings.cell_size;
+        builder.line(&[Point2 { x: 0.0, y }, Point2 { x: width, y }], 1.0, Color::WHITE)?;
     }
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,104 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, MeshBuilder, Rect, Text, TextFragment};
+
+use crate::analysis::CoverageMap;
+use crate::level::Level;
+use crate::settings::Settings;
+use crate::towers::{Tower, TowerType};
+
+const LOW_COLOR: Color = Color::new(0.2, 0.4, 1.0, 0.2);
+const HIGH_COLOR: Color = Color::new(1.0, 0.25, 0.1, 0.6);
+
+/// Path coverage of a tower type's range, shaded from blue (little path in range) to red (the
+/// most). Built the first time the overlay is shown for the tower type.
+pub struct Heatmap {
+    pub tower_type: TowerType,
+    pub coverage: CoverageMap,
+    /// `None` when no cell reaches the path.
+    mesh: Option<Mesh>,
+}
+
+impl Heatmap {
+    pub fn new(ctx: &mut Context, level: &Level, settings: &Settings, tower_type: TowerType) -> GameResult<Self> {
+        let coverage = CoverageMap::new(level, Tower::new((0, 0), tower_type).range);
+        let best = coverage.best().path_length;
+
+        let mut builder = MeshBuilder::new();
+        let mut empty = true;
+        for ((x, y), cell) in coverage.iter().filter(|(_, cell)| cell.path_length > 0.0) {
+            let share = cell.path_length / best;
+            let color = Color::new(
+                LOW_COLOR.r + (HIGH_COLOR.r - LOW_COLOR.r) * share,
+                LOW_COLOR.g + (HIGH_COLOR.g - LOW_COLOR.g) * share,
+                LOW_COLOR.b + (HIGH_COLOR.b - LOW_COLOR.b) * share,
+                LOW_COLOR.a + (HIGH_COLOR.a - LOW_COLOR.a) * share,
+            );
+            let rect = Rect::new(
+                x as f32 * settings.cell_size,
+                y as f32 * settings.cell_size,
+                settings.cell_size,
+                settings.cell_size,
+            );
+            builder.rectangle(DrawMode::fill(), rect, color)?;
+            empty = false;
+        }
+        let mesh = if empty { None } else { Some(Mesh::from_data(ctx, builder.build())) };
+
+        Ok(Heatmap { tower_type, coverage, mesh })
+    }
+
+    pub fn render(&self, canvas: &mut Canvas) {
+        if let Some(mesh) = &self.mesh {
+            canvas.draw(mesh, graphics::DrawParam::default());
+        }
+    }
+
+    /// Describes the overlay, and the hovered cell's coverage if it has one, at the top left
+    /// of the playfield. Times are for enemies moving at `enemy_speed`.
+    pub fn render_legend(
+        &self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        position: (f32, f32),
+        hovered_cell: Option<(usize, usize)>,
+        enemy_speed: f32,
+        scale: f32,
+    ) -> GameResult {
+        let best = self.coverage.best();
+        let mut legend = format!(
+            "{} coverage: best {:.1}s in range ({} path cells)",
+            self.tower_type.name(),
+            best.time_in_range(enemy_speed),
+            best.path_cells
+        );
+        if let Some(cell) = hovered_cell.and_then(|cell| self.coverage.get(cell)) {
+            legend.push_str(&format!(
+                "  here {:.1}s ({} path cells)",
+                cell.time_in_range(enemy_speed),
+                cell.path_cells
+            ));
+        }
+        let text = Text::new(TextFragment::new(legend).scale(18.0 * scale));
+        let dims = text.measure(ctx)?;
+        let padding = 4.0 * scale;
+        let background = Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(
+                position.0 - padding,
+                position.1 - padding,
+                dims.x + 2.0 * padding,
+                dims.y + 2.0 * padding,
+            ),
+            Color::new(0.0, 0.0, 0.0, 0.6),
+        )?;
+        canvas.draw(&background, graphics::DrawParam::default());
+        canvas.draw(
+            &text,
+            graphics::DrawParam::default()
+                .dest([position.0, position.1])
+                .color(Color::WHITE),
+        );
+        Ok(())
+    }
+}
@@ -1,34 +1,348 @@
 mod grid;
 mod path;
+mod effects;
 mod enemies;
 mod towers;
+mod placement;
+mod frame_stats;
+mod heatmap;
+mod tiles;
+pub mod theme;
 pub mod tower_menu;
 
+use std::time::Instant;
+
 use ggez::{Context, GameResult};
-use ggez::graphics::Canvas;
+use ggez::graphics::{self, Canvas, Color, DrawMode, InstanceArray, Mesh, MeshBuilder, Rect, Sampler};
 
+use crate::camera::Camera;
+use crate::entities::EnemyId;
+use crate::events::GameEvent;
 use crate::game_controller::GameController;
+use crate::level::Level;
 use crate::settings::Settings;
-use crate::ui::status_strip;
+use crate::towers::TowerType;
+use crate::ui::layout::{Layout, GAME_SPEEDS};
+use crate::ui::{inspect_panel, status_strip};
+
+use effects::Effects;
+use frame_stats::FrameStats;
+use heatmap::Heatmap;
+use path::Chevrons;
+use theme::Theme;
+use towers::TowerInstances;
+
+/// What the inspection panel shows. An enemy's handle stops resolving once it dies or leaks.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Selection {
+    Tower((usize, usize)),
+    Enemy(EnemyId),
+}
+
+/// Interface state of the playing scene that affects what gets drawn on top of the game.
+pub struct ViewState {
+    pub layout: Layout,
+    pub camera: Camera,
+    pub tower_menu_open: bool,
+    pub tower_menu_position: (usize, usize),
+    /// The tower option under the cursor while the tower menu is open.
+    pub menu_hovered_tower: Option<TowerType>,
+    /// Tower type of the last build hotkey, previewed under the cursor until cancelled.
+    pub hotkey_tower: Option<TowerType>,
+    pub hovered_cell: Option<(usize, usize)>,
+    pub selection: Option<Selection>,
+    /// Index into `GAME_SPEEDS`.
+    pub speed_index: usize,
+    pub show_frame_stats: bool,
+    /// Tower type whose path coverage is shaded over the map, if any.
+    pub heatmap: Option<TowerType>,
+}
+
+impl ViewState {
+    pub fn new(layout: Layout, camera: Camera) -> Self {
+        ViewState {
+            layout,
+            camera,
+            tower_menu_open: false,
+            tower_menu_position: (0, 0),
+            menu_hovered_tower: None,
+            hotkey_tower: None,
+            hovered_cell: None,
+            selection: None,
+            speed_index: 0,
+            show_frame_stats: false,
+            heatmap: None,
+        }
+    }
+
+    /// The tower about to be placed and the cell it would go on: the hovered row of the tower
+    /// menu at the menu's cell, or else the last build hotkey's tower under the cursor.
+    pub fn placement_preview(&self) -> Option<((usize, usize), TowerType)> {
+        match self.menu_hovered_tower.filter(|_| self.tower_menu_open) {
+            Some(tower_type) => Some((self.tower_menu_position, tower_type)),
+            None if self.tower_menu_open => None,
+            None => Some((self.hovered_cell?, self.hotkey_tower?)),
+        }
+    }
+
+    pub fn selected_tower(&self) -> Option<(usize, usize)> {
+        match self.selection {
+            Some(Selection::Tower(position)) => Some(position),
+            _ => None,
+        }
+    }
+
+    /// Shows the coverage heatmap of the next attacking tower type, or hides it after the last.
+    pub fn cycle_heatmap(&mut self) {
+        let mut attackers = TowerType::ALL.into_iter().filter(|t| t.attacks());
+        self.heatmap = match self.heatmap {
+            None => attackers.next(),
+            Some(current) => attackers.skip_while(|&t| t != current).nth(1),
+        };
+    }
+
+    pub fn game_speed(&self) -> f32 {
+        GAME_SPEEDS[self.speed_index]
+    }
+
+    /// Applies a new window size to the layout and the camera.
+    pub fn resize(&mut self, settings: &Settings) {
+        self.layout = Layout::new(settings);
+        self.camera.set_viewport(self.layout.playfield);
+    }
+
+    /// Screen position of the tower menu. It opens beside its cell so the cell and the
+    /// placement preview stay visible, and is kept inside the window.
+    pub fn tower_menu_anchor(&self, settings: &Settings) -> (f32, f32) {
+        let (x, y) = self.camera.world_to_screen(
+            (self.tower_menu_position.0 + 1) as f32 * settings.cell_size,
+            self.tower_menu_position.1 as f32 * settings.cell_size,
+        );
+        let (width, height) = tower_menu::menu_size(self.layout.scale);
+        let playfield = self.layout.playfield;
+        (
+            x.min(playfield.right() - width).max(playfield.x),
+            y.min(playfield.bottom() - height).max(playfield.y),
+        )
+    }
+}
+
+/// Draws a level and everything on it. Static geometry is built once when the renderer is
+/// created; enemies and towers are drawn through instance arrays refilled every frame. With a
+/// theme, whatever it has sprites for is drawn from its sheet instead of as plain shapes.
+pub struct Renderer {
+    theme: Option<Theme>,
+    /// Terrain and path tiles, when the theme has them.
+    tiles: Option<InstanceArray>,
+    level_mesh: Mesh,
+    chevrons: Chevrons,
+    enemy_mesh: Mesh,
+    enemy_instances: InstanceArray,
+    enemy_sprite_instances: InstanceArray,
+    health_bar_instances: InstanceArray,
+    tower_instances: TowerInstances,
+    heatmaps: Vec<Heatmap>,
+    effects: Effects,
+    frame_stats: FrameStats,
+}
+
+impl Renderer {
+    pub fn new(ctx: &mut Context, level: &Level, settings: &Settings) -> GameResult<Self> {
+        let theme = theme::load_theme(ctx, &settings.theme);
+        let tiles = theme.as_ref().and_then(|theme| tiles::build_tiles(ctx, level, settings, theme));
+
+        let mut builder = MeshBuilder::new();
+        grid::build_grid(&mut builder, level, settings)?;
+        if !theme.as_ref().is_some_and(tiles::draws_road) {
+            path::build_road(&mut builder, level, settings)?;
+        }
+        path::build_gates(&mut builder, level, settings)?;
+        let level_mesh = Mesh::from_data(ctx, builder.build());
+
+        // Unit circle, scaled to the enemy radius per instance
+        let enemy_mesh = Mesh::new_circle(ctx, DrawMode::fill(), [0.0, 0.0], 1.0, 0.01, Color::WHITE)?;
+
+        let sheet = theme.as_ref().map(|theme| theme.image.clone());
+        Ok(Renderer {
+            tiles,
+            level_mesh,
+            chevrons: Chevrons::new(ctx, level, settings)?,
+            enemy_mesh,
+            enemy_instances: InstanceArray::new(ctx, None),
+            enemy_sprite_instances: InstanceArray::new(ctx, sheet.clone()),
+            health_bar_instances: InstanceArray::new(ctx, None),
+            tower_instances: TowerInstances::new(ctx, sheet),
+            theme,
+            heatmaps: Vec::new(),
+            effects: Effects::new(),
+            frame_stats: FrameStats::new(),
+        })
+    }
+
+    /// Starts the effects of a game event, if effects are on.
+    pub fn handle_event(&mut self, event: &GameEvent, game_controller: &GameController, settings: &Settings) {
+        if settings.effects {
+            self.effects.handle(event, &game_controller.towers);
+        }
+    }
+
+    /// Advances effects by `delta_time` seconds of game time.
+    pub fn update_effects(&mut self, delta_time: f32, settings: &Settings) {
+        if settings.effects {
+            self.effects.update(delta_time);
+        } else {
+            self.effects.clear();
+        }
+    }
+
+    pub fn render_game(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        game_controller: &GameController,
+        settings: &Settings,
+        view: &ViewState,
+    ) -> GameResult {
+        let draw_start = Instant::now();
+
+        // World-space passes go through the camera and are clipped to the playfield
+        canvas.set_screen_coordinates(
+            view.camera.world_screen_coordinates(settings.window_width, settings.window_height),
+        );
+        canvas.set_scissor_rect(view.layout.playfield)?;
+
+        // Sprites are pixel art: scale them up without blurring
+        canvas.set_sampler(Sampler::nearest_clamp());
+        if let Some(tiles) = &self.tiles {
+            canvas.draw(tiles, graphics::DrawParam::default());
+        }
+        canvas.set_default_sampler();
+        canvas.draw(&self.level_mesh, graphics::DrawParam::default());
+        self.chevrons.render(canvas, game_controller.total_time.as_secs_f32());
+
+        let heatmap = match view.heatmap {
+            Some(tower_type) => Some(self.heatmap(ctx, &game_controller.level, settings, tower_type)?),
+            None => None,
+        };
+        if let Some(index) = heatmap {
+            self.heatmaps[index].render(canvas);
+        }
+
+        enemies::fill_enemy_instances(
+            &mut self.enemy_instances,
+            &mut self.enemy_sprite_instances,
+            &mut self.health_bar_instances,
+            game_controller,
+            settings,
+            self.theme.as_ref(),
+        );
+        canvas.draw_instanced_mesh(self.enemy_mesh.clone(), &self.enemy_instances, graphics::DrawParam::default());
+        canvas.set_sampler(Sampler::nearest_clamp());
+        canvas.draw(&self.enemy_sprite_instances, graphics::DrawParam::default());
+        canvas.set_default_sampler();
+        canvas.draw(&self.health_bar_instances, graphics::DrawParam::default());
+
+        self.tower_instances.fill(game_controller, settings, self.theme.as_ref());
+        self.tower_instances.draw(canvas);
+
+        if settings.effects {
+            self.effects.render(ctx, canvas, settings)?;
+        }
+
+        // Ranges of the selected tower and of the tower under the cursor
+        if let Some(position) = view.selected_tower() {
+            render_range_at(ctx, canvas, game_controller, settings, position)?;
+        }
+        if let Some(position) = view.hovered_cell.filter(|&cell| Some(cell) != view.selected_tower()) {
+            render_range_at(ctx, canvas, game_controller, settings, position)?;
+        }
+
+        if let Some(Selection::Enemy(handle)) = view.selection {
+            if let Some(enemy) = game_controller.enemy(handle) {
+                placement::render_enemy_marker(ctx, canvas, settings, enemy)?;
+            }
+        }
+
+        if let Some((position, tower_type)) = view.placement_preview() {
+            placement::render_placement_preview(ctx, canvas, game_controller, settings, position, tower_type)?;
+        }
 
-pub fn render_game(
+        // Screen-space passes
+        canvas.set_default_scissor_rect();
+        canvas.set_screen_coordinates(Rect::new(0.0, 0.0, settings.window_width, settings.window_height));
+
+        if view.tower_menu_open {
+            tower_menu::render_tower_menu(ctx, canvas, view.tower_menu_anchor(settings), view.layout.scale)?;
+        }
+
+        if let Some(selection) = view.selection {
+            inspect_panel::render_inspect_panel(ctx, canvas, game_controller, &view.layout, selection)?;
+        }
+
+        status_strip::render_status_strip(
+            ctx,
+            canvas,
+            game_controller,
+            settings,
+            &view.layout,
+            view.speed_index,
+        )?;
+
+        if let Some(index) = heatmap {
+            let playfield = view.layout.playfield;
+            let margin = 10.0 * view.layout.scale;
+            let wave = game_controller.game_stats.wave.max(1);
+            self.heatmaps[index].render_legend(
+                ctx,
+                canvas,
+                (playfield.x + margin, playfield.y + margin / 2.0),
+                view.hovered_cell,
+                game_controller.wave_composition(settings, wave).speed,
+                view.layout.scale,
+            )?;
+        }
+
+        self.frame_stats.record(ctx.time.delta(), draw_start.elapsed());
+        if view.show_frame_stats {
+            let playfield = view.layout.playfield;
+            let margin = 10.0 * view.layout.scale;
+            self.frame_stats.render(
+                ctx,
+                canvas,
+                playfield.right() - margin,
+                playfield.y + margin / 2.0,
+                view.layout.scale,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Index of the heatmap of a tower type, building it the first time it is asked for.
+    fn heatmap(
+        &mut self,
+        ctx: &mut Context,
+        level: &Level,
+        settings: &Settings,
+        tower_type: TowerType,
+    ) -> GameResult<usize> {
+        if let Some(index) = self.heatmaps.iter().position(|heatmap| heatmap.tower_type == tower_type) {
+            return Ok(index);
+        }
+        self.heatmaps.push(Heatmap::new(ctx, level, settings, tower_type)?);
+        Ok(self.heatmaps.len() - 1)
+    }
+}
+
+fn render_range_at(
     ctx: &mut Context,
     canvas: &mut Canvas,
     game_controller: &GameController,
     settings: &Settings,
-    tower_menu_open: bool,
-    tower_menu_position: (usize, usize),
+    position: (usize, usize),
 ) -> GameResult {
-    grid::render_grid(ctx, canvas, game_controller, settings)?;
-    path::render_path(ctx, canvas, game_controller, settings)?;
-    enemies::render_enemies(ctx, canvas, game_controller, settings)?;
-    towers::render_towers(ctx, canvas, game_controller, settings)?;
-    
-    if tower_menu_open {
-        tower_menu::render_tower_menu(ctx, canvas, tower_menu_position, settings)?;
+    match game_controller.tower_at(position) {
+        Some(handle) => placement::render_tower_range(ctx, canvas, settings, &game_controller.towers[handle]),
+        None => Ok(()),
     }
-
-    status_strip::render_status_strip(ctx, canvas, &game_controller.game_stats, settings)?;
-
-    Ok(())
-}
\ No newline at end of file
+}
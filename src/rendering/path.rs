@@ -1,50 +1,262 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
 use ggez::{Context, GameResult};
-use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect};
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, InstanceArray, Mesh, MeshBuilder, Rect};
 use ggez::mint::Point2;
 
-use crate::game_controller::GameController;
+use crate::level::{Level, Point};
 use crate::settings::Settings;
 
-pub fn render_path(
-    ctx: &mut Context,
-    canvas: &mut Canvas,
-    game_controller: &GameController,
-    settings: &Settings,
-) -> GameResult {
-    // Draw path
-    let path_points: Vec<Point2<f32>> = game_controller.level.path.iter().map(|p| {
-        Point2 {
-            x: (p.x as f32 + 0.5) * settings.cell_size,
-            y: (p.y as f32 + 0.5) * settings.cell_size,
+const CURB_COLOR: Color = Color::new(0.25, 0.22, 0.2, 1.0);
+const ROAD_COLOR: Color = Color::new(0.5, 0.45, 0.38, 1.0);
+const CHEVRON_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.45);
+/// Width of the road's edges, as a share of the road width.
+const CURB_SHARE: f32 = 0.12;
+/// Cells between chevrons along the path, and how fast they move along it in cells per second.
+const CHEVRON_SPACING: f32 = 2.0;
+const CHEVRON_SPEED: f32 = 1.0;
+
+/// A side of a cell. Angles are clockwise from east, as the screen's y axis points down.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    East,
+    South,
+    West,
+    North,
+}
+
+impl Direction {
+    /// The side of `from` that faces `to`, if they are neighbours.
+    pub fn between(from: Point, to: Point) -> Option<Direction> {
+        match (to.x as i64 - from.x as i64, to.y as i64 - from.y as i64) {
+            (1, 0) => Some(Direction::East),
+            (0, 1) => Some(Direction::South),
+            (-1, 0) => Some(Direction::West),
+            (0, -1) => Some(Direction::North),
+            _ => None,
         }
-    }).collect();
-
-    let path_mesh = Mesh::new_line(
-        ctx,
-        &path_points,
-        settings.path_width,
-        Color::WHITE,
-    )?;
-    canvas.draw(&path_mesh, graphics::DrawParam::default());
-
-    // Draw waypoints
-    for (point, color) in game_controller.level.get_path_colors() {
-        let rect = Rect::new(
-            point.x as f32 * settings.cell_size,
-            point.y as f32 * settings.cell_size,
-            settings.cell_size,
-            settings.cell_size,
-        );
+    }
 
-        let mesh = Mesh::new_rectangle(
-            ctx,
+    pub fn angle(self) -> f32 {
+        match self {
+            Direction::East => 0.0,
+            Direction::South => FRAC_PI_2,
+            Direction::West => PI,
+            Direction::North => -FRAC_PI_2,
+        }
+    }
+
+    pub fn clockwise(self) -> Direction {
+        match self {
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+            Direction::North => Direction::East,
+        }
+    }
+
+    fn vector(self) -> (f32, f32) {
+        match self {
+            Direction::East => (1.0, 0.0),
+            Direction::South => (0.0, 1.0),
+            Direction::West => (-1.0, 0.0),
+            Direction::North => (0.0, -1.0),
+        }
+    }
+}
+
+/// Each path cell with the sides the road leaves it through: towards the cells before and
+/// after it on the path. Only the order of the path counts, so two stretches of road that run
+/// side by side stay apart.
+pub fn road_links(level: &Level) -> Vec<(Point, Vec<Direction>)> {
+    let path = &level.path;
+    (0..path.len())
+        .map(|index| {
+            let before = index.checked_sub(1).map(|before| path[before]);
+            let links = [before, path.get(index + 1).copied()]
+                .into_iter()
+                .flatten()
+                .filter_map(|neighbour| Direction::between(path[index], neighbour))
+                .collect();
+            (path[index], links)
+        })
+        .collect()
+}
+
+/// Adds the path as a road to a mesh: a square in every path cell, widened into the sides it
+/// links to, so that straights, corners and ends join up. Built once per level, not every frame.
+pub fn build_road(builder: &mut MeshBuilder, level: &Level, settings: &Settings) -> GameResult {
+    let cell = settings.cell_size;
+    let width = settings.path_width * cell;
+    let links = road_links(level);
+
+    // Every piece's edge first, then every piece's surface, so neighbours cover the edges
+    // between them and only the road's outline is left
+    for (inset, color) in [(0.0, CURB_COLOR), (width * CURB_SHARE, ROAD_COLOR)] {
+        let half = width / 2.0 - inset;
+        for (point, directions) in &links {
+            let center = ((point.x as f32 + 0.5) * cell, (point.y as f32 + 0.5) * cell);
+            builder.rectangle(
+                DrawMode::fill(),
+                Rect::new(center.0 - half, center.1 - half, half * 2.0, half * 2.0),
+                color,
+            )?;
+            for direction in directions {
+                let arm = match direction {
+                    Direction::East => Rect::new(center.0, center.1 - half, cell / 2.0, half * 2.0),
+                    Direction::South => Rect::new(center.0 - half, center.1, half * 2.0, cell / 2.0),
+                    Direction::West => Rect::new(center.0 - cell / 2.0, center.1 - half, cell / 2.0, half * 2.0),
+                    Direction::North => Rect::new(center.0 - half, center.1 - cell / 2.0, half * 2.0, cell / 2.0),
+                };
+                builder.rectangle(DrawMode::fill(), arm, color)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds the spawn and exit gates and the waypoint markers to a mesh. The spawn gate has an
+/// arrow pointing down the road, the exit gate a target, so they read apart without color.
+pub fn build_gates(builder: &mut MeshBuilder, level: &Level, settings: &Settings) -> GameResult {
+    let cell = settings.cell_size;
+    let center = |point: Point| Point2 {
+        x: (point.x as f32 + 0.5) * cell,
+        y: (point.y as f32 + 0.5) * cell,
+    };
+    let frame = |point: Point| {
+        let inset = cell * 0.08;
+        Rect::new(
+            point.x as f32 * cell + inset,
+            point.y as f32 * cell + inset,
+            cell - inset * 2.0,
+            cell - inset * 2.0,
+        )
+    };
+    let colors = path_colors(level);
+    let (start_color, end_color) = (colors[0].1, colors[colors.len() - 1].1);
+    let line_width = (cell * 0.1).max(2.0);
+
+    builder.rectangle(DrawMode::stroke(line_width), frame(level.start), start_color)?;
+    let out = level.path.get(1).and_then(|&next| Direction::between(level.start, next));
+    if let Some(direction) = out {
+        let (dx, dy) = direction.vector();
+        let (c, size) = (center(level.start), cell * 0.25);
+        builder.polygon(
             DrawMode::fill(),
-            rect,
-            color,
+            &[
+                Point2 { x: c.x + dx * size, y: c.y + dy * size },
+                Point2 { x: c.x - dx * size - dy * size, y: c.y - dy * size + dx * size },
+                Point2 { x: c.x - dx * size + dy * size, y: c.y - dy * size - dx * size },
+            ],
+            start_color,
         )?;
+    }
+
+    builder.rectangle(DrawMode::stroke(line_width), frame(level.end), end_color)?;
+    builder.circle(DrawMode::stroke(line_width), center(level.end), cell * 0.25, 0.5, end_color)?;
+    builder.circle(DrawMode::fill(), center(level.end), cell * 0.1, 0.5, end_color)?;
 
-        canvas.draw(&mesh, graphics::DrawParam::default());
+    // Waypoints, between the gates in path order
+    for &(point, color) in &colors[1..colors.len() - 1] {
+        builder.circle(DrawMode::fill(), center(point), cell * 0.12, 0.5, color)?;
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The start, the waypoints and the end of the path, with the color of each: red for the start,
+/// blue for the end and hues in between for the waypoints.
+fn path_colors(level: &Level) -> Vec<(Point, Color)> {
+    let total_points = 2 + level.waypoints.len(); // start, waypoints, and end
+    let mut colors = vec![];
+
+    colors.push((level.start, Color::RED));
+
+    for (i, &waypoint) in level.waypoints.iter().enumerate() {
+        let hue = (i + 1) as f32 / total_points as f32;
+        colors.push((waypoint, hsv_to_rgb(hue, 1.0, 1.0)));
+    }
+
+    colors.push((level.end, Color::BLUE));
+
+    colors
+}
+
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Color {
+    let c = v * s;
+    let x = c * (1.0 - ((h * 6.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = match (h * 6.0).floor() as i32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    Color::new(r + m, g + m, b + m, 1.0)
+}
+
+/// Arrows that move along the road in the direction enemies travel.
+pub struct Chevrons {
+    mesh: Mesh,
+    instances: InstanceArray,
+    /// Centers of the path cells, in pixels.
+    centers: Vec<Point2<f32>>,
+    directions: Vec<Direction>,
+}
+
+impl Chevrons {
+    pub fn new(ctx: &mut Context, level: &Level, settings: &Settings) -> GameResult<Self> {
+        let cell = settings.cell_size;
+        let size = cell * 0.18;
+        // Pointing east, centered on the origin
+        let mesh = Mesh::new_polyline(
+            ctx,
+            DrawMode::stroke((cell * 0.07).max(1.5)),
+            &[
+                Point2 { x: -size / 2.0, y: -size },
+                Point2 { x: size / 2.0, y: 0.0 },
+                Point2 { x: -size / 2.0, y: size },
+            ],
+            CHEVRON_COLOR,
+        )?;
+        let centers = level
+            .path
+            .iter()
+            .map(|point| Point2 {
+                x: (point.x as f32 + 0.5) * cell,
+                y: (point.y as f32 + 0.5) * cell,
+            })
+            .collect();
+        let directions = level
+            .path
+            .windows(2)
+            .filter_map(|pair| Direction::between(pair[0], pair[1]))
+            .collect();
+        Ok(Chevrons { mesh, instances: InstanceArray::new(ctx, None), centers, directions })
+    }
+
+    /// Draws the chevrons where they are `time` seconds into the game.
+    pub fn render(&mut self, canvas: &mut Canvas, time: f32) {
+        let first = (time * CHEVRON_SPEED) % CHEVRON_SPACING;
+        let steps = self.directions.len() as f32;
+        let (centers, directions) = (&self.centers, &self.directions);
+        self.instances.set(
+            (0..)
+                .map(|index| first + index as f32 * CHEVRON_SPACING)
+                .take_while(|&distance| distance < steps)
+                .map(|distance| {
+                    let step = distance as usize;
+                    let along = distance.fract();
+                    let (from, to) = (centers[step], centers[step + 1]);
+                    DrawParam::default()
+                        .dest([from.x + (to.x - from.x) * along, from.y + (to.y - from.y) * along])
+                        .rotation(directions[step].angle())
+                }),
+        );
+        canvas.draw_instanced_mesh(self.mesh.clone(), &self.instances, DrawParam::default());
+    }
+}
@@ -0,0 +1,107 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect};
+use ggez::mint::Point2;
+
+use crate::entities::Grunt;
+use crate::game_controller::GameController;
+use crate::settings::Settings;
+use crate::towers::{Tower, TowerType};
+
+use super::towers::tower_color;
+
+const INVALID_COLOR: Color = Color::new(1.0, 0.2, 0.2, 1.0);
+
+/// Draws a translucent tower on the cell it would be built on, with its range. The preview is
+/// tinted red when the tower cannot be built there.
+pub fn render_placement_preview(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    game_controller: &GameController,
+    settings: &Settings,
+    position: (usize, usize),
+    tower_type: TowerType,
+) -> GameResult {
+    let ghost = Tower::new(position, tower_type);
+    let valid = game_controller.check_placement(position, tower_type).is_ok();
+    let base_color = if valid { tower_color(tower_type) } else { INVALID_COLOR };
+
+    render_range(ctx, canvas, settings, &ghost, valid)?;
+
+    let ghost_rect = Rect::new(
+        position.0 as f32 * settings.cell_size,
+        position.1 as f32 * settings.cell_size,
+        settings.cell_size,
+        settings.cell_size,
+    );
+    let ghost_mesh = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        ghost_rect,
+        Color::new(base_color.r, base_color.g, base_color.b, 0.5),
+    )?;
+    canvas.draw(&ghost_mesh, graphics::DrawParam::default());
+
+    Ok(())
+}
+
+/// Draws the range circle of a placed tower.
+pub fn render_tower_range(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    settings: &Settings,
+    tower: &Tower,
+) -> GameResult {
+    render_range(ctx, canvas, settings, tower, true)
+}
+
+fn render_range(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    settings: &Settings,
+    tower: &Tower,
+    valid: bool,
+) -> GameResult {
+    let center = Point2 {
+        x: (tower.position.0 as f32 + 0.5) * settings.cell_size,
+        y: (tower.position.1 as f32 + 0.5) * settings.cell_size,
+    };
+    let radius = tower.range * settings.cell_size;
+    if radius <= 0.0 {
+        return Ok(());
+    }
+    let (fill, outline) = if valid {
+        (Color::new(1.0, 1.0, 1.0, 0.12), Color::new(1.0, 1.0, 1.0, 0.7))
+    } else {
+        (Color::new(1.0, 0.2, 0.2, 0.15), Color::new(1.0, 0.2, 0.2, 0.8))
+    };
+
+    let area = Mesh::new_circle(ctx, DrawMode::fill(), center, radius, 0.5, fill)?;
+    canvas.draw(&area, graphics::DrawParam::default());
+    let border = Mesh::new_circle(ctx, DrawMode::stroke(2.0), center, radius, 0.5, outline)?;
+    canvas.draw(&border, graphics::DrawParam::default());
+
+    Ok(())
+}
+
+/// Draws a ring around the selected enemy.
+pub fn render_enemy_marker(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    settings: &Settings,
+    enemy: &Grunt,
+) -> GameResult {
+    let center = Point2 {
+        x: enemy.position.0 * settings.cell_size,
+        y: enemy.position.1 * settings.cell_size,
+    };
+    let ring = Mesh::new_circle(
+        ctx,
+        DrawMode::stroke(2.0),
+        center,
+        settings.enemy_radius * 1.4,
+        0.5,
+        Color::new(1.0, 1.0, 1.0, 0.9),
+    )?;
+    canvas.draw(&ring, graphics::DrawParam::default());
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::io::Read;
+
+use ggez::Context;
+use ggez::graphics::{DrawParam, Image, Rect};
+use serde::Deserialize;
+use toml::Table;
+
+use crate::towers::TowerType;
+use crate::waves::EnemyKind;
+
+/// Directory of the theme packs in the resources, one directory per theme.
+pub const THEMES_DIR: &str = "/themes";
+/// Theme name that turns sprites off.
+pub const NO_THEME: &str = "none";
+
+/// Something that can be drawn with a sprite.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpriteKey {
+    Tower(TowerType),
+    /// Drawn over the tower and turned to face its target, so it should point east.
+    Turret(TowerType),
+    Enemy(EnemyKind),
+    /// A whole path cell, for themes without road pieces.
+    Path,
+    /// Road pieces, turned to fit the path: a straight piece running west to east, a corner
+    /// joining east and south, and a dead end open to the east.
+    RoadStraight,
+    RoadCorner,
+    RoadEnd,
+    Terrain,
+}
+
+impl SpriteKey {
+    const ALL: [SpriteKey; 15] = [
+        SpriteKey::Tower(TowerType::Gun),
+        SpriteKey::Tower(TowerType::Sniper),
+        SpriteKey::Tower(TowerType::Flame),
+        SpriteKey::Tower(TowerType::Bank),
+        SpriteKey::Turret(TowerType::Gun),
+        SpriteKey::Turret(TowerType::Sniper),
+        SpriteKey::Turret(TowerType::Flame),
+        SpriteKey::Enemy(EnemyKind::Grunt),
+        SpriteKey::Enemy(EnemyKind::Runner),
+        SpriteKey::Enemy(EnemyKind::Brute),
+        SpriteKey::Path,
+        SpriteKey::RoadStraight,
+        SpriteKey::RoadCorner,
+        SpriteKey::RoadEnd,
+        SpriteKey::Terrain,
+    ];
+
+    /// Name of the sprite in theme files.
+    fn name(self) -> &'static str {
+        match self {
+            SpriteKey::Tower(TowerType::Gun) => "gun",
+            SpriteKey::Tower(TowerType::Sniper) => "sniper",
+            SpriteKey::Tower(TowerType::Flame) => "flame",
+            SpriteKey::Tower(TowerType::Bank) => "bank",
+            SpriteKey::Turret(TowerType::Gun) => "gun_turret",
+            SpriteKey::Turret(TowerType::Sniper) => "sniper_turret",
+            SpriteKey::Turret(TowerType::Flame) => "flame_turret",
+            // Banks do not attack, so they have no turret, and the key is not in ALL
+            SpriteKey::Turret(TowerType::Bank) => "",
+            SpriteKey::Enemy(EnemyKind::Grunt) => "grunt",
+            SpriteKey::Enemy(EnemyKind::Runner) => "runner",
+            SpriteKey::Enemy(EnemyKind::Brute) => "brute",
+            SpriteKey::Path => "path",
+            SpriteKey::RoadStraight => "road_straight",
+            SpriteKey::RoadCorner => "road_corner",
+            SpriteKey::RoadEnd => "road_end",
+            SpriteKey::Terrain => "terrain",
+        }
+    }
+}
+
+/// Frames of one sprite in the theme's sheet, as source rectangles relative to the sheet.
+pub struct Sprite {
+    frames: Vec<Rect>,
+    fps: f32,
+}
+
+impl Sprite {
+    /// The frame to show `time` seconds into the game.
+    pub fn frame(&self, time: f32) -> Rect {
+        let index = (time * self.fps) as usize % self.frames.len();
+        self.frames[index]
+    }
+
+    pub fn first_frame(&self) -> Rect {
+        self.frames[0]
+    }
+}
+
+/// A theme pack: a sprite sheet and where each sprite is in it. Whatever the theme leaves
+/// out is drawn with plain shapes.
+pub struct Theme {
+    pub image: Image,
+    sprites: Vec<(SpriteKey, Sprite)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    /// Sheet image, relative to the theme's directory.
+    image: String,
+    /// Width and height of a frame in the sheet, in pixels.
+    tile_size: u32,
+    #[serde(default)]
+    sprites: Table,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpriteFile {
+    row: u32,
+    #[serde(default)]
+    column: u32,
+    /// Frames, laid out left to right from `column`.
+    #[serde(default = "one")]
+    frames: u32,
+    /// Frames per second of the animation.
+    #[serde(default)]
+    fps: f32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl Theme {
+    /// Loads `<THEMES_DIR>/<name>/theme.toml` and its sheet through the game's filesystem.
+    pub fn load(ctx: &Context, name: &str) -> Result<Self, String> {
+        let directory = format!("{}/{}", THEMES_DIR, name);
+        let path = format!("{}/theme.toml", directory);
+        let mut contents = String::new();
+        ctx.fs
+            .open(&path)
+            .map_err(|err| err.to_string())?
+            .read_to_string(&mut contents)
+            .map_err(|err| format!("{}: {}", path, err))?;
+        let file: ThemeFile = toml::from_str(&contents).map_err(|err| format!("{}: {}", path, err))?;
+        if file.tile_size == 0 {
+            return Err(format!("{}: tile_size must be at least 1", path));
+        }
+
+        let image = Image::from_path(ctx, format!("{}/{}", directory, file.image)).map_err(|err| err.to_string())?;
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        let tile = file.tile_size as f32;
+
+        let mut sprites = Vec::new();
+        for (sprite_name, value) in file.sprites {
+            let key = SpriteKey::ALL
+                .into_iter()
+                .find(|key| key.name() == sprite_name)
+                .ok_or_else(|| {
+                    let names: Vec<_> = SpriteKey::ALL.iter().map(|key| key.name()).collect();
+                    format!("{}: unknown sprite '{}', expected one of {}", path, sprite_name, names.join(", "))
+                })?;
+            let sprite: SpriteFile = value
+                .try_into()
+                .map_err(|err| format!("{}: sprite '{}': {}", path, sprite_name, err))?;
+            if sprite.frames == 0 || !sprite.fps.is_finite() || sprite.fps < 0.0 {
+                return Err(format!("{}: sprite '{}' needs at least one frame and fps of 0 or more", path, sprite_name));
+            }
+            let outside = || format!("{}: sprite '{}' lies outside {}", path, sprite_name, file.image);
+            let last_column = sprite.column.checked_add(sprite.frames).ok_or_else(outside)?;
+            let last_row = sprite.row.checked_add(1).ok_or_else(outside)?;
+            if last_column as f32 * tile > width || last_row as f32 * tile > height {
+                return Err(outside());
+            }
+            let frames = (sprite.column..last_column)
+                .map(|column| {
+                    Rect::new(
+                        column as f32 * tile / width,
+                        sprite.row as f32 * tile / height,
+                        tile / width,
+                        tile / height,
+                    )
+                })
+                .collect();
+            sprites.push((key, Sprite { frames, fps: sprite.fps }));
+        }
+
+        Ok(Theme { image, sprites })
+    }
+
+    pub fn sprite(&self, key: SpriteKey) -> Option<&Sprite> {
+        self.sprites.iter().find(|(k, _)| *k == key).map(|(_, sprite)| sprite)
+    }
+
+    /// Draws a frame of the sheet stretched over `area`.
+    pub fn draw_param(&self, frame: Rect, area: Rect) -> DrawParam {
+        self.draw_param_rotated(frame, area, 0.0)
+    }
+
+    /// Draws a frame of the sheet stretched over `area` and turned clockwise by `rotation`
+    /// radians about its center.
+    pub fn draw_param_rotated(&self, frame: Rect, area: Rect, rotation: f32) -> DrawParam {
+        // Instances are scaled to the frame's size in pixels before their own transform, so
+        // the offset is in the frame's pixels
+        let frame_width = frame.w * self.image.width() as f32;
+        let frame_height = frame.h * self.image.height() as f32;
+        DrawParam::default()
+            .src(frame)
+            .dest([area.x + area.w / 2.0, area.y + area.h / 2.0])
+            .offset([frame_width / 2.0, frame_height / 2.0])
+            .rotation(rotation)
+            .scale([area.w / frame_width, area.h / frame_height])
+    }
+}
+
+/// Names of the theme packs in the resources, sorted, followed by `NO_THEME`.
+pub fn available_themes(ctx: &Context) -> Vec<String> {
+    let mut names: Vec<String> = ctx
+        .fs
+        .read_dir(THEMES_DIR)
+        .map(|entries| {
+            entries
+                .filter(|path| ctx.fs.exists(path.join("theme.toml")))
+                .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.dedup();
+    names.push(NO_THEME.to_string());
+    names
+}
+
+/// Loads the theme chosen in the settings. A theme that fails to load is reported and
+/// replaced by plain shapes, so broken assets never stop the game.
+pub fn load_theme(ctx: &Context, name: &str) -> Option<Theme> {
+    if name == NO_THEME {
+        return None;
+    }
+    match Theme::load(ctx, name) {
+        Ok(theme) => Some(theme),
+        Err(err) => {
+            eprintln!("warning: theme '{}' not loaded, drawing plain shapes: {}", name, err);
+            None
+        }
+    }
+}
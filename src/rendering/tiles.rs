@@ -0,0 +1,80 @@
+use ggez::Context;
+use ggez::graphics::{InstanceArray, Rect};
+
+use crate::level::Level;
+use crate::settings::Settings;
+
+use super::path::{self, Direction};
+use super::theme::{Sprite, SpriteKey, Theme};
+
+/// Whether the theme draws the path itself, with road pieces or whole path tiles. Without
+/// either the road is drawn as plain shapes.
+pub fn draws_road(theme: &Theme) -> bool {
+    road_pieces(theme).is_some() || theme.sprite(SpriteKey::Path).is_some()
+}
+
+/// The straight and corner pieces, which are enough to build any road, and the dead end if
+/// the theme has one.
+fn road_pieces(theme: &Theme) -> Option<(&Sprite, &Sprite, Option<&Sprite>)> {
+    Some((
+        theme.sprite(SpriteKey::RoadStraight)?,
+        theme.sprite(SpriteKey::RoadCorner)?,
+        theme.sprite(SpriteKey::RoadEnd),
+    ))
+}
+
+/// Builds the terrain and path tiles of a level from the theme's first frames. Built once per
+/// level, not every frame. `None` when the theme has no sprite for either.
+pub fn build_tiles(ctx: &mut Context, level: &Level, settings: &Settings, theme: &Theme) -> Option<InstanceArray> {
+    let terrain = theme.sprite(SpriteKey::Terrain);
+    if terrain.is_none() && !draws_road(theme) {
+        return None;
+    }
+
+    let cell = |x: usize, y: usize| {
+        Rect::new(
+            x as f32 * settings.cell_size,
+            y as f32 * settings.cell_size,
+            settings.cell_size,
+            settings.cell_size,
+        )
+    };
+    let mut tiles = InstanceArray::new(ctx, theme.image.clone());
+    if let Some(terrain) = terrain {
+        for y in 0..level.height {
+            for x in 0..level.width {
+                tiles.push(theme.draw_param(terrain.first_frame(), cell(x, y)));
+            }
+        }
+    }
+    if let Some((straight, corner, end)) = road_pieces(theme) {
+        for (point, links) in path::road_links(level) {
+            let (sprite, rotation) = road_piece(&links, straight, corner, end);
+            tiles.push(theme.draw_param_rotated(sprite.first_frame(), cell(point.x, point.y), rotation));
+        }
+    } else if let Some(path) = theme.sprite(SpriteKey::Path) {
+        for point in &level.path {
+            tiles.push(theme.draw_param(path.first_frame(), cell(point.x, point.y)));
+        }
+    }
+    Some(tiles)
+}
+
+/// The piece that joins a cell's links, and how far to turn it clockwise.
+fn road_piece<'a>(
+    links: &[Direction],
+    straight: &'a Sprite,
+    corner: &'a Sprite,
+    end: Option<&'a Sprite>,
+) -> (&'a Sprite, f32) {
+    match *links {
+        [a, b] if b == a.clockwise() => (corner, a.angle()),
+        [a, b] if a == b.clockwise() => (corner, b.angle()),
+        [a, _] => (straight, a.angle()),
+        [a] => match end {
+            Some(end) => (end, a.angle()),
+            None => (straight, a.angle()),
+        },
+        _ => (straight, 0.0),
+    }
+}
@@ -1,23 +1,31 @@
 use ggez::{Context, GameResult};
 use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect, Text, TextFragment};
 
-use crate::settings::Settings;
 use crate::towers::TowerType;
 
+use super::towers::tower_color;
+
 const MENU_WIDTH: f32 = 300.0;  // Doubled from 150.0
-const MENU_HEIGHT: f32 = 180.0;  // Doubled from 120.0 and reduced by one row
+const MENU_HEIGHT: f32 = 240.0;  // One row per tower type
 const BUTTON_HEIGHT: f32 = 60.0;  // Doubled from 30.0
 
+/// Size of the menu in screen pixels at the given HUD scale.
+pub fn menu_size(scale: f32) -> (f32, f32) {
+    (MENU_WIDTH * scale, MENU_HEIGHT * scale)
+}
+
+/// Draws the menu with its top-left corner at `anchor`, in screen coordinates.
 pub fn render_tower_menu(
     ctx: &mut Context,
     canvas: &mut Canvas,
-    tower_menu_position: (usize, usize),
-    settings: &Settings,
+    anchor: (f32, f32),
+    scale: f32,
 ) -> GameResult {
-    let menu_x = tower_menu_position.0 as f32 * settings.cell_size;
-    let menu_y = tower_menu_position.1 as f32 * settings.cell_size;
+    let (menu_x, menu_y) = anchor;
+    let (menu_width, menu_height) = menu_size(scale);
+    let button_height = BUTTON_HEIGHT * scale;
 
-    let menu_rect = Rect::new(menu_x, menu_y, MENU_WIDTH, MENU_HEIGHT);
+    let menu_rect = Rect::new(menu_x, menu_y, menu_width, menu_height);
 
     let menu_bg = Mesh::new_rectangle(
         ctx,
@@ -29,9 +37,17 @@ pub fn render_tower_menu(
     canvas.draw(&menu_bg, graphics::DrawParam::default());
 
     // Render tower options
-    render_tower_option(ctx, canvas, "Gun Tower", menu_x, menu_y, Color::BLUE)?;
-    render_tower_option(ctx, canvas, "Sniper Tower", menu_x, menu_y + BUTTON_HEIGHT, Color::RED)?;
-    render_tower_option(ctx, canvas, "Flame Tower", menu_x, menu_y + 2.0 * BUTTON_HEIGHT, Color::YELLOW)?;
+    for (index, tower_type) in TowerType::ALL.iter().enumerate() {
+        render_tower_option(
+            ctx,
+            canvas,
+            tower_type.name(),
+            menu_x,
+            menu_y + index as f32 * button_height,
+            scale,
+            tower_color(*tower_type),
+        )?;
+    }
 
     Ok(())
 }
@@ -42,9 +58,11 @@ fn render_tower_option(
     name: &str,
     x: f32,
     y: f32,
+    scale: f32,
     color: Color,
 ) -> GameResult {
-    let button_rect = Rect::new(x, y, MENU_WIDTH, BUTTON_HEIGHT);
+    let (button_width, button_height) = (MENU_WIDTH * scale, BUTTON_HEIGHT * scale);
+    let button_rect = Rect::new(x, y, button_width, button_height);
     let button_bg = Mesh::new_rectangle(
         ctx,
         DrawMode::fill(),
@@ -53,33 +71,25 @@ fn render_tower_option(
     )?;
     canvas.draw(&button_bg, graphics::DrawParam::default());
 
-    let text = Text::new(TextFragment::new(name).scale(32.0));  // Increased font size
+    let text = Text::new(TextFragment::new(name).scale(32.0 * scale));  // Increased font size
     let text_dims = text.measure(ctx)?;
     canvas.draw(
         &text,
         graphics::DrawParam::default()
             .color(Color::BLACK)
-            .dest([x + (MENU_WIDTH - text_dims.x) / 2.0, y + (BUTTON_HEIGHT - text_dims.y) / 2.0]),
+            .dest([x + (button_width - text_dims.x) / 2.0, y + (button_height - text_dims.y) / 2.0]),
     );
 
     Ok(())
 }
 
-pub fn get_selected_tower(x: f32, y: f32, tower_menu_position: (usize, usize), settings: &Settings) -> Option<TowerType> {
-    let menu_x = tower_menu_position.0 as f32 * settings.cell_size;
-    let menu_y = tower_menu_position.1 as f32 * settings.cell_size;
+pub fn get_selected_tower(x: f32, y: f32, anchor: (f32, f32), scale: f32) -> Option<TowerType> {
+    let (menu_x, menu_y) = anchor;
+    let (menu_width, menu_height) = menu_size(scale);
 
-    if x >= menu_x && x <= menu_x + MENU_WIDTH && y >= menu_y && y <= menu_y + MENU_HEIGHT {
-        let relative_y = y - menu_y;
-        if relative_y < BUTTON_HEIGHT {
-            Some(TowerType::Gun)
-        } else if relative_y < 2.0 * BUTTON_HEIGHT {
-            Some(TowerType::Sniper)
-        } else if relative_y < 3.0 * BUTTON_HEIGHT {
-            Some(TowerType::Flame)
-        } else {
-            None
-        }
+    if x >= menu_x && x <= menu_x + menu_width && y >= menu_y && y <= menu_y + menu_height {
+        let relative_y = (y - menu_y) / scale;
+        TowerType::ALL.get((relative_y / BUTTON_HEIGHT) as usize).copied()
     } else {
         None
     }
@@ -1,31 +1,143 @@
-use ggez::{Context, GameResult};
-use ggez::graphics::{self, Canvas, DrawMode, Mesh, Rect};
+use ggez::Context;
+use ggez::graphics::{Canvas, Color, DrawParam, Image, InstanceArray, Rect, Sampler};
 
 use crate::game_controller::GameController;
 use crate::settings::Settings;
+use crate::towers::{Tower, TowerType};
 
-pub fn render_towers(
-    ctx: &mut Context,
-    canvas: &mut Canvas,
-    game_controller: &GameController,
-    settings: &Settings,
-) -> GameResult {
-    for tower in &game_controller.towers {
-        let tower_rect = Rect::new(
-            tower.position.0 as f32 * settings.cell_size,
-            tower.position.1 as f32 * settings.cell_size,
-            settings.cell_size,
-            settings.cell_size,
-        );
-
-        let tower_mesh = Mesh::new_rectangle(
-            ctx,
-            DrawMode::fill(),
-            tower_rect,
-            tower.color,
-        )?;
-
-        canvas.draw(&tower_mesh, graphics::DrawParam::default());
+use super::theme::{SpriteKey, Theme};
+
+const BARREL_COLOR: Color = Color::new(0.15, 0.15, 0.18, 1.0);
+const HUB_COLOR: Color = Color::new(0.3, 0.3, 0.33, 1.0);
+/// Side of the turret's hub, in cells.
+const HUB_SIZE: f32 = 0.36;
+/// Seconds a turret takes to slide back into place after a shot, and how far it is pushed
+/// back by it, in cells.
+const RECOIL_TIME: f32 = 0.12;
+const RECOIL_DISTANCE: f32 = 0.12;
+
+/// Color of a tower drawn without a sprite, and of its button in the tower menu.
+pub fn tower_color(tower_type: TowerType) -> Color {
+    match tower_type {
+        TowerType::Gun => Color::BLUE,
+        TowerType::Sniper => Color::RED,
+        TowerType::Flame => Color::YELLOW,
+        TowerType::Bank => Color::new(0.85, 0.65, 0.13, 1.0),
+    }
+}
+
+/// Length and width of a tower's barrel, in cells.
+fn barrel_size(tower_type: TowerType) -> (f32, f32) {
+    match tower_type {
+        TowerType::Gun => (0.45, 0.14),
+        TowerType::Sniper => (0.55, 0.08),
+        TowerType::Flame => (0.35, 0.22),
+        TowerType::Bank => (0.0, 0.0),
+    }
+}
+
+/// Per-frame instance arrays for towers: the bases, then the turrets turned to face their
+/// targets on top. Each layer has plain quads and, for what the theme has sprites for, sprites.
+pub struct TowerInstances {
+    bases: InstanceArray,
+    base_sprites: InstanceArray,
+    turrets: InstanceArray,
+    turret_sprites: InstanceArray,
+}
+
+impl TowerInstances {
+    pub fn new(ctx: &mut Context, sheet: Option<Image>) -> Self {
+        TowerInstances {
+            bases: InstanceArray::new(ctx, None),
+            base_sprites: InstanceArray::new(ctx, sheet.clone()),
+            turrets: InstanceArray::new(ctx, None),
+            turret_sprites: InstanceArray::new(ctx, sheet),
+        }
     }
-    Ok(())
-}
\ No newline at end of file
+
+    pub fn fill(&mut self, game_controller: &GameController, settings: &Settings, theme: Option<&Theme>) {
+        let time = game_controller.total_time.as_secs_f32();
+        let sprite_of = |key| theme.and_then(|theme| theme.sprite(key).map(|s| (theme, s)));
+        let cell = |tower: &Tower| {
+            Rect::new(
+                tower.position.0 as f32 * settings.cell_size,
+                tower.position.1 as f32 * settings.cell_size,
+                settings.cell_size,
+                settings.cell_size,
+            )
+        };
+
+        self.bases.clear();
+        self.base_sprites.clear();
+        self.turrets.clear();
+        self.turret_sprites.clear();
+        for tower in game_controller.towers.values() {
+            let area = cell(tower);
+            match sprite_of(SpriteKey::Tower(tower.tower_type)) {
+                Some((theme, sprite)) => self.base_sprites.push(theme.draw_param(sprite.frame(time), area)),
+                None => self.bases.push(
+                    DrawParam::default()
+                        .dest([area.x, area.y])
+                        .scale([area.w, area.h])
+                        .color(tower_color(tower.tower_type)),
+                ),
+            }
+
+            if !tower.tower_type.attacks() {
+                continue;
+            }
+            // The turret slides back along its barrel when it fires
+            let recoil = recoil(tower, game_controller) * settings.cell_size;
+            let (cos, sin) = (tower.facing.cos(), tower.facing.sin());
+            let center = [
+                area.x + area.w / 2.0 - cos * recoil,
+                area.y + area.h / 2.0 - sin * recoil,
+            ];
+            match sprite_of(SpriteKey::Turret(tower.tower_type)) {
+                Some((theme, sprite)) => {
+                    let area = Rect::new(center[0] - area.w / 2.0, center[1] - area.h / 2.0, area.w, area.h);
+                    self.turret_sprites.push(theme.draw_param_rotated(sprite.frame(time), area, tower.facing));
+                }
+                None => {
+                    let (length, width) = barrel_size(tower.tower_type);
+                    self.turrets.push(
+                        DrawParam::default()
+                            .dest(center)
+                            .offset([0.0, 0.5])
+                            .rotation(tower.facing)
+                            .scale([length * settings.cell_size, width * settings.cell_size])
+                            .color(BARREL_COLOR),
+                    );
+                    self.turrets.push(
+                        DrawParam::default()
+                            .dest(center)
+                            .offset([0.5, 0.5])
+                            .rotation(tower.facing)
+                            .scale([HUB_SIZE * settings.cell_size, HUB_SIZE * settings.cell_size])
+                            .color(HUB_COLOR),
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        canvas.draw(&self.bases, DrawParam::default());
+        canvas.set_sampler(Sampler::nearest_clamp());
+        canvas.draw(&self.base_sprites, DrawParam::default());
+        canvas.set_default_sampler();
+        canvas.draw(&self.turrets, DrawParam::default());
+        canvas.set_sampler(Sampler::nearest_clamp());
+        canvas.draw(&self.turret_sprites, DrawParam::default());
+        canvas.set_default_sampler();
+    }
+}
+
+/// How far back the turret is pushed by its last shot, in cells.
+fn recoil(tower: &Tower, game_controller: &GameController) -> f32 {
+    let since_shot = match tower.last_shot_time {
+        Some(shot) => game_controller.total_time.saturating_sub(shot).as_secs_f32(),
+        None => return 0.0,
+    };
+    RECOIL_DISTANCE * (1.0 - since_shot / RECOIL_TIME).max(0.0)
+}
@@ -0,0 +1,187 @@
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::{self, Canvas, Color, Text, TextFragment};
+
+use crate::game_stats::GameStats;
+use crate::input::Action;
+use crate::settings::Settings;
+
+use super::{Scene, Transition};
+
+const TITLE_SIZE: f32 = 40.0;
+const TEXT_SIZE: f32 = 20.0;
+const LINE_HEIGHT: f32 = 26.0;
+const MARGIN: f32 = 40.0;
+/// Number of towers listed individually, by damage dealt.
+const TOP_TOWERS: usize = 5;
+
+const HEADING_COLOR: Color = Color::new(1.0, 0.85, 0.4, 1.0);
+const ROW_COLOR: Color = Color::new(0.9, 0.9, 0.9, 1.0);
+
+/// End-of-game tables of combat statistics per tower type, for the best towers and per wave.
+/// The wave table scrolls with the mouse wheel when it does not fit.
+pub struct BreakdownScene {
+    game_stats: GameStats,
+    wave_scroll: usize,
+}
+
+impl BreakdownScene {
+    pub fn new(game_stats: GameStats) -> Self {
+        BreakdownScene {
+            game_stats,
+            wave_scroll: 0,
+        }
+    }
+
+    fn tower_type_rows(&self) -> Vec<String> {
+        let mut rows = vec![format!(
+            "{:<14}{:>6}{:>8}{:>8}{:>10}{:>10}{:>7}{:>8}",
+            "Type", "Built", "Spent", "Shots", "Damage", "Overkill", "Kills", "Dmg/$"
+        )];
+        rows.extend(self.game_stats.by_tower_type().iter().map(|summary| {
+            format!(
+                "{:<14}{:>6}{:>8}{:>8}{:>10.0}{:>10.0}{:>7}{:>8.2}",
+                summary.tower_type.name(),
+                summary.towers_built,
+                summary.money_spent,
+                summary.shots_fired,
+                summary.damage_dealt,
+                summary.overkill,
+                summary.kills,
+                summary.efficiency
+            )
+        }));
+        rows
+    }
+
+    fn top_tower_rows(&self) -> Vec<String> {
+        let mut towers: Vec<_> = self.game_stats.towers.iter().collect();
+        towers.sort_by(|a, b| b.damage_dealt.total_cmp(&a.damage_dealt));
+
+        let mut rows = vec![format!(
+            "{:<20}{:>8}{:>7}{:>8}{:>10}{:>7}{:>8}",
+            "Tower", "Cell", "Wave", "Spent", "Damage", "Kills", "Dmg/$"
+        )];
+        rows.extend(towers.iter().take(TOP_TOWERS).map(|record| {
+            let name = if record.sold {
+                format!("{} (sold)", record.tower_type.name())
+            } else {
+                record.tower_type.name().to_string()
+            };
+            format!(
+                "{:<20}{:>8}{:>7}{:>8}{:>10.0}{:>7}{:>8.2}",
+                name,
+                format!("{},{}", record.position.0, record.position.1),
+                record.built_in_wave,
+                record.money_spent,
+                record.damage_dealt,
+                record.kills,
+                record.efficiency()
+            )
+        }));
+        rows
+    }
+
+    fn wave_header() -> String {
+        format!("{:<8}{:>8}{:>8}{:>10}", "Wave", "Kills", "Leaks", "Earned")
+    }
+
+    fn wave_rows(&self) -> Vec<String> {
+        self.game_stats
+            .waves
+            .iter()
+            .map(|wave| format!("{:<8}{:>8}{:>8}{:>10}", wave.wave, wave.kills, wave.leaks, wave.money_earned))
+            .collect()
+    }
+}
+
+fn draw_line(canvas: &mut Canvas, text: &str, x: f32, y: f32, size: f32, color: Color) {
+    let text = Text::new(TextFragment::new(text).scale(size));
+    canvas.draw(&text, graphics::DrawParam::default().dest([x, y]).color(color));
+}
+
+impl Scene for BreakdownScene {
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        let scale = settings.ui_scale;
+        let line_height = LINE_HEIGHT * scale;
+        let x = MARGIN * scale;
+        let mut y = MARGIN * scale;
+
+        draw_line(canvas, "Breakdown", x, y, TITLE_SIZE * scale, Color::WHITE);
+        y += 2.0 * line_height;
+
+        let sections = [
+            ("By tower type", self.tower_type_rows()),
+            ("Top towers by damage", self.top_tower_rows()),
+        ];
+        for (heading, rows) in sections {
+            draw_line(canvas, heading, x, y, TEXT_SIZE * scale, HEADING_COLOR);
+            y += line_height;
+            for row in rows {
+                draw_line(canvas, &row, x, y, TEXT_SIZE * scale, ROW_COLOR);
+                y += line_height;
+            }
+            y += line_height;
+        }
+
+        draw_line(canvas, "By wave", x, y, TEXT_SIZE * scale, HEADING_COLOR);
+        y += line_height;
+        draw_line(canvas, &Self::wave_header(), x, y, TEXT_SIZE * scale, ROW_COLOR);
+        y += line_height;
+
+        let footer_y = settings.window_height - MARGIN * scale;
+        let rows = self.wave_rows();
+        let visible = ((footer_y - y) / line_height).floor().max(0.0) as usize;
+        self.wave_scroll = self.wave_scroll.min(rows.len().saturating_sub(visible));
+        for row in rows.iter().skip(self.wave_scroll).take(visible) {
+            draw_line(canvas, row, x, y, TEXT_SIZE * scale, ROW_COLOR);
+            y += line_height;
+        }
+
+        draw_line(
+            canvas,
+            "Scroll to see more waves. Click or press Escape to go back.",
+            x,
+            footer_y,
+            TEXT_SIZE * scale,
+            Color::new(0.6, 0.6, 0.6, 1.0),
+        );
+        Ok(())
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult<Transition> {
+        match button {
+            MouseButton::Left => Ok(Transition::Pop),
+            _ => Ok(Transition::None),
+        }
+    }
+
+    fn mouse_wheel(&mut self, _ctx: &mut Context, y: f32) -> GameResult {
+        // The upper bound is applied when drawing, where the number of visible rows is known
+        if y > 0.0 {
+            self.wave_scroll = self.wave_scroll.saturating_sub(1);
+        } else if y < 0.0 {
+            self.wave_scroll += 1;
+        }
+        Ok(())
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Cancel | Action::Confirm => Ok(Transition::Pop),
+            _ => Ok(Transition::None),
+        }
+    }
+}
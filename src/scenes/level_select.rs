@@ -0,0 +1,75 @@
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::Canvas;
+
+use crate::input::Action;
+use crate::settings::Settings;
+use crate::ui::menu::Menu;
+
+use super::{PlayingScene, Scene, Transition};
+
+/// Seeds of the hand-picked maps offered on the level select screen.
+const LEVEL_SEEDS: [u64; 5] = [1, 7, 42, 1337, 90210];
+
+pub struct LevelSelectScene {
+    menu: Menu,
+}
+
+impl LevelSelectScene {
+    pub fn new() -> Self {
+        LevelSelectScene {
+            menu: Menu::new("Level Select", Vec::new()),
+        }
+    }
+
+    fn refresh(&mut self, settings: &Settings) {
+        let mut items = vec![format!("Current map (seed {})", settings.seed)];
+        items.extend((1..=LEVEL_SEEDS.len()).map(|level| format!("Level {}", level)));
+        items.push("Random map".to_string());
+        items.push("Back".to_string());
+        self.menu.items = items;
+    }
+}
+
+impl Scene for LevelSelectScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        self.refresh(settings);
+        self.menu.draw(ctx, canvas, settings)
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if button != MouseButton::Left {
+            return Ok(Transition::None);
+        }
+        self.refresh(settings);
+        let back = self.menu.items.len() - 1;
+        let random = back - 1;
+        match self.menu.item_at(x, y, settings) {
+            Some(0) => {}
+            Some(index) if index == back => return Ok(Transition::Pop),
+            Some(index) if index == random => settings.seed = rand::random(),
+            Some(index) => settings.seed = LEVEL_SEEDS[index - 1],
+            None => return Ok(Transition::None),
+        }
+        Ok(Transition::Replace(Box::new(PlayingScene::new(ctx, settings)?)))
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Cancel => Ok(Transition::Pop),
+            _ => Ok(Transition::None),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::Canvas;
+
+use crate::input::Action;
+use crate::settings::Settings;
+use crate::ui::menu::Menu;
+
+use super::{LevelSelectScene, PlayingScene, Scene, SettingsScene, Transition};
+
+const PLAY: usize = 0;
+const LEVEL_SELECT: usize = 1;
+const SETTINGS: usize = 2;
+const QUIT: usize = 3;
+
+pub struct MainMenuScene {
+    menu: Menu,
+}
+
+impl MainMenuScene {
+    pub fn new() -> Self {
+        MainMenuScene {
+            menu: Menu::new(
+                "Tower Defense",
+                vec![
+                    "Play".to_string(),
+                    "Level Select".to_string(),
+                    "Settings".to_string(),
+                    "Quit".to_string(),
+                ],
+            ),
+        }
+    }
+}
+
+impl Scene for MainMenuScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        self.menu.lines = vec![format!("Difficulty: {} ({})", settings.difficulty, settings.mode)];
+        self.menu.draw(ctx, canvas, settings)
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if button != MouseButton::Left {
+            return Ok(Transition::None);
+        }
+        Ok(match self.menu.item_at(x, y, settings) {
+            Some(PLAY) => Transition::Push(Box::new(PlayingScene::new(ctx, settings)?)),
+            Some(LEVEL_SELECT) => Transition::Push(Box::new(LevelSelectScene::new())),
+            Some(SETTINGS) => Transition::Push(Box::new(SettingsScene::new())),
+            Some(QUIT) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+
+    fn action(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Confirm => Ok(Transition::Push(Box::new(PlayingScene::new(ctx, settings)?))),
+            Action::Cancel => Ok(Transition::Quit),
+            _ => Ok(Transition::None),
+        }
+    }
+}
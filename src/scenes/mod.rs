@@ -0,0 +1,154 @@
+mod breakdown;
+mod level_select;
+mod main_menu;
+mod paused;
+mod playing;
+mod results;
+mod settings_menu;
+
+pub use breakdown::BreakdownScene;
+pub use level_select::LevelSelectScene;
+pub use main_menu::MainMenuScene;
+pub use paused::PausedScene;
+pub use playing::PlayingScene;
+pub use results::ResultsScene;
+pub use settings_menu::SettingsScene;
+
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::Canvas;
+
+use crate::input::Action;
+use crate::settings::Settings;
+
+/// What the scene stack should do after a scene has handled an event.
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    /// Clears the whole stack and starts over with the given scene.
+    Reset(Box<dyn Scene>),
+    Quit,
+}
+
+/// One screen of the game. Only the scene on top of the stack receives updates and input.
+pub trait Scene {
+    fn update(&mut self, _ctx: &mut Context, _settings: &Settings) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult;
+
+    /// Called after the window size in `settings` changed.
+    fn resize(&mut self, _settings: &Settings) {}
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn mouse_button_up(&mut self, _ctx: &mut Context, _button: MouseButton) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_motion(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &Settings,
+        _position: (f32, f32),
+        _delta: (f32, f32),
+    ) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_wheel(&mut self, _ctx: &mut Context, _y: f32) -> GameResult {
+        Ok(())
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        _action: Action,
+    ) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    /// Called when the key of a held action is released.
+    fn action_released(&mut self, _action: Action) {}
+
+    /// Called when another scene is pushed on top. Releases that happen while it is covered
+    /// never reach this scene, so held keys and buttons should be let go here.
+    fn focus_lost(&mut self) {}
+
+    /// Overlays are drawn on top of the scene below them instead of replacing it.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(root: Box<dyn Scene>) -> Self {
+        SceneStack { scenes: vec![root] }
+    }
+
+    pub fn top(&mut self) -> Option<&mut Box<dyn Scene>> {
+        self.scenes.last_mut()
+    }
+
+    /// Applies a transition. Returns false once the game should quit.
+    pub fn apply(&mut self, transition: Transition) -> bool {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.focus_lost();
+                }
+                self.scenes.push(scene);
+            }
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::Reset(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.scenes.clear(),
+        }
+        !self.scenes.is_empty()
+    }
+
+    /// Tells every scene on the stack, not just the top one, about a new window size.
+    pub fn resize(&mut self, settings: &Settings) {
+        for scene in &mut self.scenes {
+            scene.resize(settings);
+        }
+    }
+
+    /// Draws the top scene, preceded by every scene it overlays.
+    pub fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        let mut first_visible = self.scenes.len().saturating_sub(1);
+        while first_visible > 0 && self.scenes[first_visible].is_overlay() {
+            first_visible -= 1;
+        }
+        for scene in &mut self.scenes[first_visible..] {
+            scene.draw(ctx, canvas, settings)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect};
+
+use crate::input::Action;
+use crate::settings::Settings;
+use crate::ui::menu::Menu;
+
+use super::{MainMenuScene, PlayingScene, Scene, Transition};
+
+const RESUME: usize = 0;
+const RESTART: usize = 1;
+const MAIN_MENU: usize = 2;
+const QUIT: usize = 3;
+
+pub struct PausedScene {
+    menu: Menu,
+}
+
+impl PausedScene {
+    pub fn new() -> Self {
+        PausedScene {
+            menu: Menu::new(
+                "Paused",
+                vec![
+                    "Resume".to_string(),
+                    "Restart".to_string(),
+                    "Main Menu".to_string(),
+                    "Quit".to_string(),
+                ],
+            ),
+        }
+    }
+}
+
+impl Scene for PausedScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        let shade = Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(0.0, 0.0, settings.window_width, settings.window_height),
+            Color::new(0.0, 0.0, 0.0, 0.6),
+        )?;
+        canvas.draw(&shade, graphics::DrawParam::default());
+        self.menu.draw(ctx, canvas, settings)
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if button != MouseButton::Left {
+            return Ok(Transition::None);
+        }
+        Ok(match self.menu.item_at(x, y, settings) {
+            Some(RESUME) => Transition::Pop,
+            Some(RESTART) => Transition::Reset(Box::new(PlayingScene::new(ctx, settings)?)),
+            Some(MAIN_MENU) => Transition::Reset(Box::new(MainMenuScene::new())),
+            Some(QUIT) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Cancel | Action::Pause => Ok(Transition::Pop),
+            _ => Ok(Transition::None),
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashSet;
+
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::Canvas;
+
+use crate::bot::BotDriver;
+use crate::camera::{Camera, PAN_SPEED};
+use crate::events;
+use crate::game_controller::GameController;
+use crate::input::Action;
+use crate::rendering::{self, Renderer, Selection, ViewState};
+use crate::settings::Settings;
+use crate::towers::TowerType;
+use crate::ui::layout::{Layout, GAME_SPEEDS};
+
+use super::{PausedScene, ResultsScene, Scene, Transition};
+
+/// Distance from the window edge, in pixels, at which the cursor scrolls the view.
+const EDGE_SCROLL_MARGIN: f32 = 12.0;
+const ZOOM_STEP: f32 = 1.1;
+
+pub struct PlayingScene {
+    game_controller: GameController,
+    renderer: Renderer,
+    view: ViewState,
+    held_pan_actions: HashSet<Action>,
+    dragging: bool,
+    /// Plays alongside the player when a bot was chosen on the command line.
+    bot: Option<BotDriver>,
+}
+
+impl PlayingScene {
+    pub fn new(ctx: &mut Context, settings: &Settings) -> GameResult<Self> {
+        let game_controller = GameController::new(settings);
+        let renderer = Renderer::new(ctx, &game_controller.level, settings)?;
+        let layout = Layout::new(settings);
+        let camera = Camera::new(
+            game_controller.level.width as f32 * settings.cell_size,
+            game_controller.level.height as f32 * settings.cell_size,
+            layout.playfield,
+        );
+        Ok(PlayingScene {
+            game_controller,
+            renderer,
+            view: ViewState::new(layout, camera),
+            held_pan_actions: HashSet::new(),
+            dragging: false,
+            bot: settings.bot.map(BotDriver::new),
+        })
+    }
+
+    /// The grid cell under a screen position. Cells hidden by the inspection panel are not
+    /// reachable while it is open.
+    fn cell_at(&self, x: f32, y: f32, settings: &Settings) -> Option<(usize, usize)> {
+        if self.over_side_panel(x, y) {
+            return None;
+        }
+        self.view.camera.screen_to_grid(x, y, settings.cell_size)
+    }
+
+    fn over_side_panel(&self, x: f32, y: f32) -> bool {
+        self.view.selection.is_some() && self.view.layout.side_panel().contains([x, y])
+    }
+
+    /// The tower or enemy under a screen position. Towers win, since enemies pass over them
+    /// only at the edge of their cell.
+    fn pick(&self, x: f32, y: f32, settings: &Settings) -> Option<Selection> {
+        let position = self.cell_at(x, y, settings)?;
+        if self.game_controller.tower_at(position).is_some() {
+            return Some(Selection::Tower(position));
+        }
+        let (world_x, world_y) = self.view.camera.screen_to_world(x, y);
+        let world_position = (world_x / settings.cell_size, world_y / settings.cell_size);
+        self.game_controller
+            .enemy_at(world_position, settings.enemy_radius / settings.cell_size)
+            .map(Selection::Enemy)
+    }
+
+    /// Reacts to what happened in the game since the last frame.
+    fn handle_events(&mut self, settings: &Settings) {
+        let time = self.game_controller.total_time;
+        let events: Vec<_> = self.game_controller.drain_events().collect();
+        for event in &events {
+            if settings.log_events {
+                events::log(time, event);
+            }
+            self.renderer.handle_event(event, &self.game_controller, settings);
+        }
+    }
+
+    /// Drops the selection once its tower is sold or its enemy dies or leaks.
+    fn validate_selection(&mut self) {
+        let exists = match self.view.selection {
+            Some(Selection::Tower(position)) => self.game_controller.tower_at(position).is_some(),
+            Some(Selection::Enemy(handle)) => self.game_controller.enemy(handle).is_some(),
+            None => true,
+        };
+        if !exists {
+            self.view.selection = None;
+        }
+    }
+
+    fn hovered_cell(&self, ctx: &Context, settings: &Settings) -> Option<(usize, usize)> {
+        let mouse_pos = ctx.mouse.position();
+        self.cell_at(mouse_pos.x, mouse_pos.y, settings)
+    }
+
+    /// Builds from a hotkey. The tower type stays previewed under the cursor for the next build.
+    fn build(&mut self, position: (usize, usize), tower_type: TowerType) {
+        self.view.hotkey_tower = Some(tower_type);
+        if self.game_controller.add_tower(position, tower_type) {
+            self.view.tower_menu_open = false;
+        }
+    }
+
+    /// Scrolls the view while pan keys are held or the cursor rests at the window edge.
+    fn update_camera(&mut self, ctx: &Context, settings: &Settings) {
+        let real_delta = ctx.time.delta().as_secs_f32();
+        let mut direction = (0.0, 0.0);
+        for action in &self.held_pan_actions {
+            match action {
+                Action::PanUp => direction.1 -= 1.0,
+                Action::PanDown => direction.1 += 1.0,
+                Action::PanLeft => direction.0 -= 1.0,
+                Action::PanRight => direction.0 += 1.0,
+                _ => {}
+            }
+        }
+
+        let mouse_pos = ctx.mouse.position();
+        let in_window = mouse_pos.x >= 0.0
+            && mouse_pos.y >= 0.0
+            && mouse_pos.x < settings.window_width
+            && mouse_pos.y < settings.window_height;
+        let margin = EDGE_SCROLL_MARGIN * self.view.layout.scale;
+        if in_window && !self.dragging {
+            if mouse_pos.x < margin {
+                direction.0 -= 1.0;
+            } else if mouse_pos.x > settings.window_width - margin {
+                direction.0 += 1.0;
+            }
+            if mouse_pos.y < margin {
+                direction.1 -= 1.0;
+            } else if mouse_pos.y > settings.window_height - margin {
+                direction.1 += 1.0;
+            }
+        }
+
+        if direction != (0.0, 0.0) {
+            self.view.camera.pan(
+                direction.0 * PAN_SPEED * real_delta,
+                direction.1 * PAN_SPEED * real_delta,
+            );
+        }
+    }
+}
+
+impl Scene for PlayingScene {
+    fn update(&mut self, ctx: &mut Context, settings: &Settings) -> GameResult<Transition> {
+        self.update_camera(ctx, settings);
+
+        let delta_time = ctx.time.delta().as_secs_f32() * self.view.game_speed();
+        if let Some(bot) = &mut self.bot {
+            bot.update(&mut self.game_controller, delta_time);
+        }
+        self.game_controller.update(settings, delta_time);
+        self.renderer.update_effects(delta_time, settings);
+        self.handle_events(settings);
+        self.validate_selection();
+
+        if let Some(outcome) = self.game_controller.outcome() {
+            return Ok(Transition::Replace(Box::new(ResultsScene::new(
+                outcome,
+                self.game_controller.game_stats.clone(),
+                settings,
+            ))));
+        }
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        self.renderer.render_game(ctx, canvas, &self.game_controller, settings, &self.view)
+    }
+
+    fn resize(&mut self, settings: &Settings) {
+        self.view.resize(settings);
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if let Some(index) = self.view.layout.speed_button_at(x, y).filter(|_| button == MouseButton::Left) {
+            self.view.speed_index = index;
+            return Ok(Transition::None);
+        }
+        if button == MouseButton::Left && self.view.layout.call_wave_button().contains([x, y]) {
+            self.game_controller.call_next_wave(settings);
+            return Ok(Transition::None);
+        }
+        match button {
+            MouseButton::Right => {
+                if let Some(position) = self.cell_at(x, y, settings) {
+                    self.view.tower_menu_open = true;
+                    self.view.tower_menu_position = position;
+                    self.view.menu_hovered_tower = None;
+                }
+            }
+            MouseButton::Left if self.view.tower_menu_open => {
+                let anchor = self.view.tower_menu_anchor(settings);
+                if let Some(tower_type) =
+                    rendering::tower_menu::get_selected_tower(x, y, anchor, self.view.layout.scale)
+                {
+                    if self.game_controller.add_tower(self.view.tower_menu_position, tower_type) {
+                        self.view.tower_menu_open = false;
+                    }
+                } else {
+                    self.view.tower_menu_open = false;
+                }
+            }
+            MouseButton::Left if self.over_side_panel(x, y) => {}
+            MouseButton::Left => self.view.selection = self.pick(x, y, settings),
+            MouseButton::Middle => self.dragging = true,
+            _ => {}
+        }
+        Ok(Transition::None)
+    }
+
+    fn mouse_button_up(&mut self, _ctx: &mut Context, button: MouseButton) -> GameResult {
+        if button == MouseButton::Middle {
+            self.dragging = false;
+        }
+        Ok(())
+    }
+
+    fn mouse_motion(
+        &mut self,
+        _ctx: &mut Context,
+        settings: &Settings,
+        position: (f32, f32),
+        delta: (f32, f32),
+    ) -> GameResult {
+        if self.dragging {
+            self.view.camera.pan(-delta.0, -delta.1);
+        }
+        self.view.hovered_cell = self.cell_at(position.0, position.1, settings);
+        self.view.menu_hovered_tower = if self.view.tower_menu_open {
+            rendering::tower_menu::get_selected_tower(
+                position.0,
+                position.1,
+                self.view.tower_menu_anchor(settings),
+                self.view.layout.scale,
+            )
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    fn mouse_wheel(&mut self, ctx: &mut Context, y: f32) -> GameResult {
+        let mouse_pos = ctx.mouse.position();
+        self.view.camera.zoom_at(ZOOM_STEP.powf(y), (mouse_pos.x, mouse_pos.y));
+        Ok(())
+    }
+
+    fn action(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        let hovered = self.hovered_cell(ctx, settings);
+        match (action, hovered) {
+            (Action::BuildGun, Some(position)) => self.build(position, TowerType::Gun),
+            (Action::BuildSniper, Some(position)) => self.build(position, TowerType::Sniper),
+            (Action::BuildFlame, Some(position)) => self.build(position, TowerType::Flame),
+            (Action::BuildBank, Some(position)) => self.build(position, TowerType::Bank),
+            (Action::Upgrade, Some(position)) => {
+                self.game_controller.upgrade_tower(position);
+            }
+            (Action::Sell, Some(position)) => {
+                if self.game_controller.sell_tower(position) && self.view.selected_tower() == Some(position) {
+                    self.view.selection = None;
+                }
+            }
+            (Action::CycleTargeting, Some(position)) => {
+                self.game_controller.cycle_targeting(position);
+            }
+            (Action::BuildGun, None) => self.view.hotkey_tower = Some(TowerType::Gun),
+            (Action::BuildSniper, None) => self.view.hotkey_tower = Some(TowerType::Sniper),
+            (Action::BuildFlame, None) => self.view.hotkey_tower = Some(TowerType::Flame),
+            (Action::BuildBank, None) => self.view.hotkey_tower = Some(TowerType::Bank),
+            (Action::Upgrade | Action::Sell | Action::CycleTargeting, None) => {}
+            (Action::PanUp | Action::PanDown | Action::PanLeft | Action::PanRight, _) => {
+                self.held_pan_actions.insert(action);
+            }
+            (Action::Speed, _) => {
+                self.view.speed_index = (self.view.speed_index + 1) % GAME_SPEEDS.len();
+            }
+            (Action::CallWave, _) => {
+                self.game_controller.call_next_wave(settings);
+            }
+            (Action::ToggleFrameStats, _) => self.view.show_frame_stats = !self.view.show_frame_stats,
+            (Action::ToggleHeatmap, _) => self.view.cycle_heatmap(),
+            (Action::Cancel, _) if self.view.tower_menu_open => self.view.tower_menu_open = false,
+            (Action::Cancel, _) if self.view.hotkey_tower.is_some() => self.view.hotkey_tower = None,
+            (Action::Cancel, _) if self.view.selection.is_some() => self.view.selection = None,
+            (Action::Cancel | Action::Pause, _) => {
+                return Ok(Transition::Push(Box::new(PausedScene::new())));
+            }
+            (Action::Confirm, _) => {}
+        }
+        Ok(Transition::None)
+    }
+
+    fn action_released(&mut self, action: Action) {
+        self.held_pan_actions.remove(&action);
+    }
+
+    fn focus_lost(&mut self) {
+        self.held_pan_actions.clear();
+        self.dragging = false;
+    }
+}
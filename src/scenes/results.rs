@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::{self, Canvas, Color, Rect, Text, TextFragment};
+
+use crate::game_controller::Outcome;
+use crate::game_stats::GameStats;
+use crate::high_scores::{HighScore, HighScores, HIGH_SCORES_PATH};
+use crate::input::Action;
+use crate::settings::Settings;
+use crate::ui::graph::render_money_graph;
+use crate::ui::menu::Menu;
+
+use super::{BreakdownScene, MainMenuScene, PlayingScene, Scene, Transition};
+
+const PLAY_AGAIN: usize = 0;
+const BREAKDOWN: usize = 1;
+const EXPORT_STATS: usize = 2;
+const MAIN_MENU: usize = 3;
+const QUIT: usize = 4;
+
+/// Space around the money graph and the high scores, before scaling.
+const GRAPH_MARGIN: f32 = 40.0;
+const TEXT_SIZE: f32 = 20.0;
+const LINE_HEIGHT: f32 = 26.0;
+
+/// The game over and victory screens.
+pub struct ResultsScene {
+    menu: Menu,
+    outcome: Outcome,
+    game_stats: GameStats,
+    seed: u64,
+    /// Line of the menu reporting the last export, once there was one.
+    export_line: Option<usize>,
+    /// This game's table of high scores, and the rank of this game in it if it made the table.
+    high_scores: Vec<HighScore>,
+    rank: Option<usize>,
+}
+
+impl ResultsScene {
+    pub fn new(outcome: Outcome, game_stats: GameStats, settings: &Settings) -> Self {
+        let seed = settings.seed;
+        let title = match outcome {
+            Outcome::Victory => "Victory!",
+            Outcome::Defeat => "Game Over",
+        };
+        let high_score = HighScore::new(&game_stats, outcome, seed, unix_time());
+        let (high_scores, rank, high_score_line) = record_high_score(high_score.clone(), settings.bot.is_some());
+        let mut lines = vec![
+            format!("Score: {}", high_score.score),
+            format!("Difficulty: {} ({})", game_stats.difficulty, game_stats.mode),
+            format!("Seed: {}", seed),
+            format!("Wave reached: {}", game_stats.wave),
+            format!("Enemies killed: {}", game_stats.enemies_killed),
+            format!("Enemies leaked: {}", game_stats.enemies_leaked),
+            format!("Money left: ${}", game_stats.economy.money()),
+        ];
+        lines.extend(high_score_line);
+        ResultsScene {
+            menu: Menu::new(
+                title,
+                vec![
+                    "Play Again".to_string(),
+                    "Breakdown".to_string(),
+                    "Export Stats".to_string(),
+                    "Main Menu".to_string(),
+                    "Quit".to_string(),
+                ],
+            )
+            .with_lines(lines),
+            outcome,
+            game_stats,
+            seed,
+            export_line: None,
+            high_scores,
+            rank,
+        }
+    }
+
+    /// Areas of the menu, the money graph and the high scores. In wide windows the graph and
+    /// the high scores are stacked right of the menu, otherwise they share a band below it.
+    fn areas(settings: &Settings) -> (Rect, Rect, Rect) {
+        let (width, height) = (settings.window_width, settings.window_height);
+        let margin = GRAPH_MARGIN * settings.ui_scale;
+        let (menu, graph, scores) = if width >= 1.2 * height {
+            (
+                Rect::new(0.0, 0.0, width / 2.0, height),
+                Rect::new(width / 2.0, 0.0, width / 2.0, height * 0.55),
+                Rect::new(width / 2.0, height * 0.55, width / 2.0, height * 0.45),
+            )
+        } else {
+            (
+                Rect::new(0.0, 0.0, width, height * 0.65),
+                Rect::new(0.0, height * 0.65, width / 2.0, height * 0.35),
+                Rect::new(width / 2.0, height * 0.65, width / 2.0, height * 0.35),
+            )
+        };
+        let inset = |area: Rect| {
+            Rect::new(
+                area.x + margin,
+                area.y + margin,
+                (area.w - 2.0 * margin).max(0.0),
+                (area.h - 2.0 * margin).max(0.0),
+            )
+        };
+        (menu, inset(graph), inset(scores))
+    }
+
+    /// Draws as much of the high score table as fits in `area`, this game's entry highlighted.
+    fn draw_high_scores(&self, canvas: &mut Canvas, area: Rect, scale: f32) {
+        let line_height = LINE_HEIGHT * scale;
+        let rows = self.high_scores.iter().enumerate().map(|(index, score)| {
+            let line = format!(
+                "{:>2}. {:>7}{:>8}{:>8}",
+                index + 1,
+                score.score,
+                score.waves_survived,
+                score.kills
+            );
+            let color = if self.rank == Some(index) {
+                Color::new(1.0, 0.85, 0.4, 1.0)
+            } else {
+                Color::new(0.85, 0.85, 0.85, 1.0)
+            };
+            (line, color)
+        });
+        let lines = [
+            ("High scores".to_string(), Color::WHITE),
+            (format!("{:<4}{:>7}{:>8}{:>8}", "", "Score", "Waves", "Kills"), Color::WHITE),
+        ];
+        let mut y = area.y;
+        for (line, color) in lines.into_iter().chain(rows) {
+            if y + line_height > area.bottom() {
+                break;
+            }
+            let text = Text::new(TextFragment::new(line).scale(TEXT_SIZE * scale));
+            canvas.draw(&text, graphics::DrawParam::default().dest([area.x, y]).color(color));
+            y += line_height;
+        }
+    }
+
+    /// Writes the statistics to a JSON file in the working directory and reports the result
+    /// on the screen.
+    fn export_stats(&mut self) {
+        let path = PathBuf::from(format!("stats-{}-{}.json", self.seed, unix_time()));
+        let message = match self.game_stats.export_json(&path, self.seed, self.outcome) {
+            Ok(()) => format!("Saved stats to {}", path.display()),
+            Err(err) => format!("Could not save stats: {}", err),
+        };
+        match self.export_line {
+            Some(index) => self.menu.lines[index] = message,
+            None => {
+                self.export_line = Some(self.menu.lines.len());
+                self.menu.lines.push(message);
+            }
+        }
+    }
+}
+
+impl Scene for ResultsScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        let (menu_area, graph_area, scores_area) = Self::areas(settings);
+        self.menu.draw_in(ctx, canvas, menu_area, settings.ui_scale)?;
+        render_money_graph(ctx, canvas, graph_area, &self.game_stats.economy, settings.ui_scale)?;
+        self.draw_high_scores(canvas, scores_area, settings.ui_scale);
+        Ok(())
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if button != MouseButton::Left {
+            return Ok(Transition::None);
+        }
+        let (menu_area, _, _) = Self::areas(settings);
+        Ok(match self.menu.item_at_in(x, y, menu_area, settings.ui_scale) {
+            Some(PLAY_AGAIN) => Transition::Reset(Box::new(PlayingScene::new(ctx, settings)?)),
+            Some(BREAKDOWN) => Transition::Push(Box::new(BreakdownScene::new(self.game_stats.clone()))),
+            Some(EXPORT_STATS) => {
+                self.export_stats();
+                Transition::None
+            }
+            Some(MAIN_MENU) => Transition::Reset(Box::new(MainMenuScene::new())),
+            Some(QUIT) => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Cancel => Ok(Transition::Reset(Box::new(MainMenuScene::new()))),
+            _ => Ok(Transition::None),
+        }
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Adds the score to the high score file, unless a bot played the game. Returns the score's
+/// table, its rank in it, and a line for the menu when it made the table or could not be saved.
+fn record_high_score(entry: HighScore, bot: bool) -> (Vec<HighScore>, Option<usize>, Option<String>) {
+    let path = Path::new(HIGH_SCORES_PATH);
+    // An unreadable file is left alone rather than replaced with this one score
+    let mut high_scores = match HighScores::load(path) {
+        Ok(high_scores) => high_scores,
+        Err(err) => return (vec![entry], None, Some(format!("Could not load high scores: {}", err))),
+    };
+    if bot {
+        let line = "Bot games are not recorded".to_string();
+        return (high_scores.table(&entry).cloned().collect(), None, Some(line));
+    }
+    let rank = high_scores.add(entry.clone());
+    let mut line = rank.map(|rank| format!("New high score: #{}", rank + 1));
+    if rank.is_some() {
+        if let Err(err) = high_scores.save(path) {
+            line = Some(format!("Could not save high scores: {}", err));
+        }
+    }
+    (high_scores.table(&entry).cloned().collect(), rank, line)
+}
@@ -0,0 +1,106 @@
+use ggez::{Context, GameResult};
+use ggez::event::MouseButton;
+use ggez::graphics::Canvas;
+
+use crate::input::Action;
+use crate::rendering::theme;
+use crate::settings::{Difficulty, GameMode, Settings, MAX_WAYPOINTS};
+use crate::ui::menu::Menu;
+
+use super::{Scene, Transition};
+
+const DIFFICULTY: usize = 0;
+const MODE: usize = 1;
+const WAYPOINTS: usize = 2;
+const EFFECTS: usize = 3;
+const THEME: usize = 4;
+const NEW_SEED: usize = 5;
+const BACK: usize = 6;
+
+pub struct SettingsScene {
+    menu: Menu,
+}
+
+impl SettingsScene {
+    pub fn new() -> Self {
+        SettingsScene {
+            menu: Menu::new("Settings", Vec::new()),
+        }
+    }
+
+    fn refresh(&mut self, settings: &Settings) {
+        self.menu.items = vec![
+            format!("Difficulty: {}", settings.difficulty),
+            format!("Mode: {}", settings.mode),
+            format!("Waypoints: {}", settings.num_waypoints),
+            format!("Effects: {}", if settings.effects { "on" } else { "off" }),
+            format!("Theme: {} (from the next game)", settings.theme),
+            format!("New map (seed {})", settings.seed),
+            "Back".to_string(),
+        ];
+    }
+}
+
+impl Scene for SettingsScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        self.refresh(settings);
+        self.menu.draw(ctx, canvas, settings)
+    }
+
+    fn mouse_button_down(
+        &mut self,
+        ctx: &mut Context,
+        settings: &mut Settings,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<Transition> {
+        if button != MouseButton::Left {
+            return Ok(Transition::None);
+        }
+        self.refresh(settings);
+        match self.menu.item_at(x, y, settings) {
+            Some(DIFFICULTY) => {
+                let index = Difficulty::ALL
+                    .iter()
+                    .position(|&difficulty| difficulty == settings.difficulty)
+                    .unwrap_or(0);
+                settings.difficulty = Difficulty::ALL[(index + 1) % Difficulty::ALL.len()];
+            }
+            Some(MODE) => {
+                let index = GameMode::ALL.iter().position(|&mode| mode == settings.mode).unwrap_or(0);
+                settings.mode = GameMode::ALL[(index + 1) % GameMode::ALL.len()];
+            }
+            Some(WAYPOINTS) => {
+                // Cycle through the counts the grid can hold, wrapping back to one.
+                settings.num_waypoints = settings.num_waypoints % MAX_WAYPOINTS + 1;
+                if settings.validate().is_err() {
+                    settings.num_waypoints = 1;
+                }
+            }
+            Some(EFFECTS) => settings.effects = !settings.effects,
+            Some(THEME) => {
+                // A theme that is set but not installed moves on to the first one
+                let themes = theme::available_themes(ctx);
+                let next = themes.iter().position(|name| *name == settings.theme).map_or(0, |index| index + 1);
+                settings.theme = themes[next % themes.len()].clone();
+            }
+            Some(NEW_SEED) => settings.seed = rand::random(),
+            Some(BACK) => return Ok(Transition::Pop),
+            _ => {}
+        }
+        Ok(Transition::None)
+    }
+
+    fn action(
+        &mut self,
+        _ctx: &mut Context,
+        _settings: &mut Settings,
+        action: Action,
+    ) -> GameResult<Transition> {
+        match action {
+            Action::Cancel => Ok(Transition::Pop),
+            _ => Ok(Transition::None),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use crate::bot::BotKind;
+
+use super::{Difficulty, GameMode, Settings, SettingsError};
+
+pub const USAGE: &str = "\
+Usage: tower_defense [OPTIONS]
+
+Options:
+  --config <PATH>      Settings file to load (default: settings.toml if present)
+  --seed <N>           Seed for level generation
+  --grid <WxH>         Grid size in cells, e.g. 30x20
+  --cell-size <PX>     Size of one grid cell in pixels
+  --money <N>          Starting money
+  --difficulty <NAME>  easy, normal, hard or nightmare
+  --mode <NAME>        classic (win after the last wave) or endless
+  --keybindings <PATH> Keybindings file to load (default: keybindings.toml if present)
+  --waves <PATH>       Wave script to load (default: waves.toml if present)
+  --bot <NAME>         Let a bot play: greedy or coverage
+  --headless           Play one game with the bot without a window and print the result
+  --log-events         Print every game event to standard error
+  --theme <NAME>       Theme pack from resources/themes, or none for plain shapes
+  -h, --help           Print this help";
+
+/// Values given on the command line. They take precedence over the settings file.
+#[derive(Default)]
+pub struct CliOverrides {
+    pub config: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub grid: Option<(usize, usize)>,
+    pub cell_size: Option<f32>,
+    pub money: Option<u32>,
+    pub difficulty: Option<Difficulty>,
+    pub mode: Option<GameMode>,
+    pub keybindings: Option<PathBuf>,
+    pub waves: Option<PathBuf>,
+    pub bot: Option<BotKind>,
+    pub headless: bool,
+    pub log_events: bool,
+    pub theme: Option<String>,
+}
+
+impl CliOverrides {
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some((width, height)) = self.grid {
+            settings.grid_width = width;
+            settings.grid_height = height;
+        }
+        if let Some(cell_size) = self.cell_size {
+            settings.cell_size = cell_size;
+        }
+        if let Some(money) = self.money {
+            settings.initial_money = money;
+        }
+        if let Some(difficulty) = self.difficulty {
+            settings.difficulty = difficulty;
+        }
+        if let Some(mode) = self.mode {
+            settings.mode = mode;
+        }
+        if let Some(path) = &self.keybindings {
+            settings.keybindings_path = Some(path.clone());
+        }
+        if let Some(path) = &self.waves {
+            settings.waves_path = Some(path.clone());
+        }
+        if let Some(bot) = self.bot {
+            settings.bot = Some(bot);
+        }
+        if let Some(theme) = &self.theme {
+            settings.theme = theme.clone();
+        }
+        settings.headless = self.headless;
+        settings.log_events = self.log_events;
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<CliOverrides, SettingsError> {
+    let mut overrides = CliOverrides::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // Accept both `--flag value` and `--flag=value`.
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| SettingsError::Argument(format!("{} needs a value", flag)))
+        };
+
+        match flag.as_str() {
+            "--config" => overrides.config = Some(PathBuf::from(value()?)),
+            "--seed" => overrides.seed = Some(parse_number(&flag, &value()?)?),
+            "--grid" => overrides.grid = Some(parse_grid(&value()?)?),
+            "--cell-size" => overrides.cell_size = Some(parse_number(&flag, &value()?)?),
+            "--money" => overrides.money = Some(parse_number(&flag, &value()?)?),
+            "--keybindings" => overrides.keybindings = Some(PathBuf::from(value()?)),
+            "--waves" => overrides.waves = Some(PathBuf::from(value()?)),
+            "--difficulty" => {
+                overrides.difficulty = Some(value()?.parse().map_err(SettingsError::Argument)?)
+            }
+            "--bot" => overrides.bot = Some(value()?.parse().map_err(SettingsError::Argument)?),
+            "--headless" => overrides.headless = true,
+            "--log-events" => overrides.log_events = true,
+            "--theme" => overrides.theme = Some(value()?),
+            "--mode" => overrides.mode = Some(value()?.parse().map_err(SettingsError::Argument)?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(SettingsError::Argument(format!(
+                    "unknown option '{}'\n\n{}",
+                    arg, USAGE
+                )))
+            }
+        }
+    }
+
+    if overrides.headless && overrides.bot.is_none() {
+        return Err(SettingsError::Argument("--headless needs a bot, e.g. --bot greedy".to_string()));
+    }
+    Ok(overrides)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, SettingsError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| SettingsError::Argument(format!("{} expects a number, got '{}'", flag, value)))
+}
+
+fn parse_grid(value: &str) -> Result<(usize, usize), SettingsError> {
+    let invalid = || {
+        SettingsError::Argument(format!(
+            "--grid expects WIDTHxHEIGHT, e.g. 30x20, got '{}'",
+            value
+        ))
+    };
+    let lower = value.to_lowercase();
+    let (width, height) = lower.split_once('x').ok_or_else(invalid)?;
+    let width = width.trim().parse().map_err(|_| invalid())?;
+    let height = height.trim().parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOverrides, SettingsError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn argument_error(args: &[&str]) -> String {
+        match parse(args) {
+            Err(SettingsError::Argument(message)) => message,
+            Err(err) => panic!("expected an argument error, got {}", err),
+            Ok(_) => panic!("expected {:?} to be rejected", args),
+        }
+    }
+
+    #[test]
+    fn parses_grid() {
+        assert_eq!(parse(&["--grid", "30x20"]).unwrap().grid, Some((30, 20)));
+        assert_eq!(parse(&["--grid", " 12X 9 "]).unwrap().grid, Some((12, 9)));
+        assert!(argument_error(&["--grid", "30"]).contains("WIDTHxHEIGHT"));
+        assert!(argument_error(&["--grid", "30xa"]).contains("WIDTHxHEIGHT"));
+    }
+
+    #[test]
+    fn accepts_inline_values() {
+        let overrides = parse(&["--seed=42", "--grid=16x12", "--difficulty=hard", "--theme=none"]).unwrap();
+        assert_eq!(overrides.seed, Some(42));
+        assert_eq!(overrides.grid, Some((16, 12)));
+        assert!(overrides.difficulty == Some(Difficulty::Hard));
+        assert_eq!(overrides.theme.as_deref(), Some("none"));
+    }
+
+    #[test]
+    fn rejects_missing_and_bad_values() {
+        assert_eq!(argument_error(&["--seed"]), "--seed needs a value");
+        assert_eq!(argument_error(&["--money", "lots"]), "--money expects a number, got 'lots'");
+        assert!(argument_error(&["--difficulty", "brutal"]).contains("brutal"));
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        assert!(argument_error(&["--speed", "2"]).starts_with("unknown option '--speed'"));
+        assert!(argument_error(&["--speed=2"]).starts_with("unknown option '--speed=2'"));
+    }
+
+    #[test]
+    fn headless_needs_a_bot() {
+        assert!(argument_error(&["--headless"]).contains("needs a bot"));
+        let overrides = parse(&["--headless", "--bot", "coverage"]).unwrap();
+        assert!(overrides.headless && overrides.bot == Some(BotKind::Coverage));
+    }
+
+    #[test]
+    fn overrides_apply_to_settings() {
+        let mut settings = Settings::new();
+        parse(&["--grid", "16x12", "--money", "900", "--mode", "endless"]).unwrap().apply(&mut settings);
+        assert_eq!((settings.grid_width, settings.grid_height), (16, 12));
+        assert_eq!(settings.initial_money, 900);
+        assert!(settings.mode == GameMode::Endless);
+    }
+}
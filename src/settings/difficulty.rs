@@ -0,0 +1,115 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+/// Multipliers applied to the base `Settings` for a difficulty level.
+#[derive(Clone, Copy)]
+pub struct DifficultyModifiers {
+    pub enemy_health: f32,
+    pub enemy_speed: f32,
+    /// Scales the time between spawns; below 1.0 enemies arrive closer together.
+    pub spawn_interval: f32,
+    pub bounty: f32,
+    pub money: f32,
+    pub lives: f32,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    pub fn modifiers(self) -> DifficultyModifiers {
+        match self {
+            Difficulty::Easy => DifficultyModifiers {
+                enemy_health: 0.75,
+                enemy_speed: 0.9,
+                spawn_interval: 1.25,
+                bounty: 1.25,
+                money: 1.5,
+                lives: 2.0,
+            },
+            Difficulty::Normal => DifficultyModifiers {
+                enemy_health: 1.0,
+                enemy_speed: 1.0,
+                spawn_interval: 1.0,
+                bounty: 1.0,
+                money: 1.0,
+                lives: 1.0,
+            },
+            Difficulty::Hard => DifficultyModifiers {
+                enemy_health: 1.4,
+                enemy_speed: 1.15,
+                spawn_interval: 0.8,
+                bounty: 0.9,
+                money: 0.8,
+                lives: 0.5,
+            },
+            Difficulty::Nightmare => DifficultyModifiers {
+                enemy_health: 2.0,
+                enemy_speed: 1.3,
+                spawn_interval: 0.6,
+                bounty: 0.75,
+                money: 0.6,
+                lives: 0.25,
+            },
+        }
+    }
+}
+
+impl DifficultyModifiers {
+    pub fn scale_money(&self, amount: u32) -> u32 {
+        (amount as f32 * self.money).round() as u32
+    }
+
+    pub fn scale_bounty(&self, amount: u32) -> u32 {
+        (amount as f32 * self.bounty).round() as u32
+    }
+
+    /// Scales the number of lives, always leaving at least one.
+    pub fn scale_lives(&self, lives: u32) -> u32 {
+        ((lives as f32 * self.lives).round() as u32).max(1)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!("unknown difficulty '{}', expected one of easy, normal, hard, nightmare", s)
+            })
+    }
+}
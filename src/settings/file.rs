@@ -0,0 +1,170 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::{Difficulty, GameMode, Settings, SettingsError};
+
+/// Contents of a settings file. Every key is optional; missing keys keep their defaults.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsFile {
+    pub grid_width: Option<usize>,
+    pub grid_height: Option<usize>,
+    pub cell_size: Option<f32>,
+    pub num_waypoints: Option<usize>,
+    pub path_width: Option<f32>,
+    pub enemy_speed: Option<f32>,
+    pub enemy_health: Option<f32>,
+    pub enemy_spawn_interval: Option<f32>,
+    pub enemies_per_wave: Option<u32>,
+    pub waves_to_win: Option<u32>,
+    pub wave_break: Option<f32>,
+    pub wave_health_growth: Option<f32>,
+    pub wave_count_growth: Option<f32>,
+    pub wave_speed_growth: Option<f32>,
+    pub runner_wave: Option<u32>,
+    pub brute_wave: Option<u32>,
+    pub initial_money: Option<u32>,
+    pub enemy_kill_reward: Option<u32>,
+    pub bounty_per_wave: Option<u32>,
+    pub wave_bonus: Option<u32>,
+    pub wave_bonus_per_wave: Option<u32>,
+    pub interest_rate: Option<f32>,
+    pub interest_cap: Option<u32>,
+    pub early_call_bonus: Option<f32>,
+    pub bank_income: Option<u32>,
+    pub initial_lives: Option<u32>,
+    pub difficulty: Option<Difficulty>,
+    pub mode: Option<GameMode>,
+    pub seed: Option<u64>,
+    pub effects: Option<bool>,
+    pub theme: Option<String>,
+}
+
+impl SettingsFile {
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(value) = self.grid_width {
+            settings.grid_width = value;
+        }
+        if let Some(value) = self.grid_height {
+            settings.grid_height = value;
+        }
+        if let Some(value) = self.cell_size {
+            settings.cell_size = value;
+        }
+        if let Some(value) = self.num_waypoints {
+            settings.num_waypoints = value;
+        }
+        if let Some(value) = self.path_width {
+            settings.path_width = value;
+        }
+        if let Some(value) = self.enemy_speed {
+            settings.enemy_speed = value;
+        }
+        if let Some(value) = self.enemy_health {
+            settings.enemy_health = value;
+        }
+        if let Some(value) = self.enemy_spawn_interval {
+            settings.enemy_spawn_interval = value;
+        }
+        if let Some(value) = self.enemies_per_wave {
+            settings.enemies_per_wave = value;
+        }
+        if let Some(value) = self.waves_to_win {
+            settings.waves_to_win = value;
+        }
+        if let Some(value) = self.wave_break {
+            settings.wave_break = value;
+        }
+        if let Some(value) = self.wave_health_growth {
+            settings.wave_health_growth = value;
+        }
+        if let Some(value) = self.wave_count_growth {
+            settings.wave_count_growth = value;
+        }
+        if let Some(value) = self.wave_speed_growth {
+            settings.wave_speed_growth = value;
+        }
+        if let Some(value) = self.runner_wave {
+            settings.runner_wave = value;
+        }
+        if let Some(value) = self.brute_wave {
+            settings.brute_wave = value;
+        }
+        if let Some(value) = self.initial_money {
+            settings.initial_money = value;
+        }
+        if let Some(value) = self.enemy_kill_reward {
+            settings.enemy_kill_reward = value;
+        }
+        if let Some(value) = self.bounty_per_wave {
+            settings.bounty_per_wave = value;
+        }
+        if let Some(value) = self.wave_bonus {
+            settings.wave_bonus = value;
+        }
+        if let Some(value) = self.wave_bonus_per_wave {
+            settings.wave_bonus_per_wave = value;
+        }
+        if let Some(value) = self.interest_rate {
+            settings.interest_rate = value;
+        }
+        if let Some(value) = self.interest_cap {
+            settings.interest_cap = value;
+        }
+        if let Some(value) = self.early_call_bonus {
+            settings.early_call_bonus = value;
+        }
+        if let Some(value) = self.bank_income {
+            settings.bank_income = value;
+        }
+        if let Some(value) = self.initial_lives {
+            settings.initial_lives = value;
+        }
+        if let Some(value) = self.difficulty {
+            settings.difficulty = value;
+        }
+        if let Some(value) = self.mode {
+            settings.mode = value;
+        }
+        if let Some(value) = self.seed {
+            settings.seed = value;
+        }
+        if let Some(value) = self.effects {
+            settings.effects = value;
+        }
+        if let Some(value) = &self.theme {
+            settings.theme = value.clone();
+        }
+    }
+}
+
+/// Reads a text file. A missing file is only an error when `required` is set, i.e. when the
+/// path was given explicitly.
+pub fn read_text_file(path: &Path, required: bool) -> Result<Option<String>, SettingsError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == ErrorKind::NotFound && !required => Ok(None),
+        Err(source) => Err(SettingsError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// Reads and parses a TOML file, with the same handling of missing files as `read_text_file`.
+pub fn read_toml_file<T: DeserializeOwned>(path: &Path, required: bool) -> Result<Option<T>, SettingsError> {
+    let Some(contents) = read_text_file(path, required)? else {
+        return Ok(None);
+    };
+
+    toml::from_str(&contents)
+        .map(Some)
+        .map_err(|err| SettingsError::Parse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+}
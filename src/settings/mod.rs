@@ -0,0 +1,374 @@
+mod cli;
+mod difficulty;
+mod file;
+mod mode;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::bot::BotKind;
+use crate::waves::WaveScript;
+
+pub use difficulty::{Difficulty, DifficultyModifiers};
+pub use file::{read_text_file, read_toml_file};
+pub use mode::GameMode;
+
+pub const DEFAULT_SETTINGS_PATH: &str = "settings.toml";
+/// Height of the HUD above the playfield at a HUD scale of 1.
+pub const HUD_HEIGHT: f32 = 40.0;
+pub const MAX_WAYPOINTS: usize = 10;
+/// Largest playfield shown at once; bigger levels are scrolled with the camera.
+pub const MAX_PLAYFIELD_WIDTH: f32 = 1600.0;
+pub const MAX_PLAYFIELD_HEIGHT: f32 = 1000.0;
+/// Smallest window the HUD and menus are laid out for, in physical pixels.
+pub const MIN_WINDOW_WIDTH: f32 = 640.0;
+pub const MIN_WINDOW_HEIGHT: f32 = 480.0;
+/// Window height, in logical pixels, at which the HUD is drawn at its full size.
+const REFERENCE_WINDOW_HEIGHT: f32 = 1040.0;
+/// Smallest HUD scale used on short windows, before the DPI factor is applied.
+const MIN_UI_SCALE: f32 = 0.6;
+/// Share of the monitor the initial window may take, leaving room for decorations and panels.
+const MAX_SCREEN_SHARE: f32 = 0.9;
+const MIN_GRID_SIZE: usize = 4;
+
+#[derive(Clone)]
+pub struct Settings {
+    pub grid_width: usize,
+    pub grid_height: usize,
+    pub cell_size: f32,
+    /// Current window size in physical pixels. Derived from the grid at startup and updated
+    /// whenever the window is resized.
+    pub window_width: f32,
+    pub window_height: f32,
+    /// Scale applied to HUD text and widgets for the current window size and DPI.
+    pub ui_scale: f32,
+    pub num_waypoints: usize,
+    /// Width of the road as a share of the cell size, from 0 to 1.
+    pub path_width: f32,
+    pub enemy_radius: f32,
+    pub enemy_speed: f32,
+    pub enemy_health: f32,
+    pub enemy_spawn_interval: f32,
+    pub enemies_per_wave: u32,
+    pub waves_to_win: u32,
+    /// Seconds between the last spawn of a wave and the first of the next.
+    pub wave_break: f32,
+    /// Health and enemy count added to procedural waves for every wave past the last scripted
+    /// one, as a share of that wave's values.
+    pub wave_health_growth: f32,
+    pub wave_count_growth: f32,
+    pub wave_speed_growth: f32,
+    /// First procedural waves to bring runners (fast and fragile) and brutes (slow and tough)
+    /// along with their regular enemies.
+    pub runner_wave: u32,
+    pub brute_wave: u32,
+    pub initial_money: u32,
+    /// Bounty for enemies of the first wave.
+    pub enemy_kill_reward: u32,
+    /// Added to the bounty for every wave after the first.
+    pub bounty_per_wave: u32,
+    /// Paid once every enemy of a wave is gone, growing by `wave_bonus_per_wave` each wave.
+    pub wave_bonus: u32,
+    pub wave_bonus_per_wave: u32,
+    /// Share of the banked money paid as interest at the end of each wave; 0 disables interest.
+    pub interest_rate: f32,
+    pub interest_cap: u32,
+    /// Money per second of the break skipped by calling a wave early.
+    pub early_call_bonus: f32,
+    /// Money a level 1 bank pays at the end of each wave.
+    pub bank_income: u32,
+    pub initial_lives: u32,
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub seed: u64,
+    /// Draw combat effects: muzzle flashes, tracers, sparks and floating numbers.
+    pub effects: bool,
+    /// Theme pack under `resources/themes` to draw sprites from; "none" draws plain shapes.
+    pub theme: String,
+    pub keybindings_path: Option<PathBuf>,
+    pub waves_path: Option<PathBuf>,
+    /// Bot that plays instead of the player, if any.
+    pub bot: Option<BotKind>,
+    /// Play one game with the bot without opening a window and print the result.
+    pub headless: bool,
+    /// Print every game event to standard error as it happens.
+    pub log_events: bool,
+    /// Waves loaded from the wave file, if there is one.
+    pub wave_script: WaveScript,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, message: String },
+    Argument(String),
+    Invalid(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            SettingsError::Parse { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+            SettingsError::Argument(message) => write!(f, "invalid argument: {}", message),
+            SettingsError::Invalid(message) => write!(f, "invalid settings: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let grid_width = 40;
+        let grid_height = 25;
+        let cell_size = 40.0;
+        let mut settings = Settings {
+            grid_width,
+            grid_height,
+            cell_size,
+            window_width: 0.0,
+            window_height: 0.0,
+            ui_scale: 1.0,
+            num_waypoints: 5,
+            path_width: 0.7,
+            enemy_radius: 0.0,
+            enemy_speed: 2.0,
+            enemy_health: 100.0,
+            enemy_spawn_interval: 5.0,
+            enemies_per_wave: 10,
+            waves_to_win: 20,
+            wave_break: 10.0,
+            wave_health_growth: 0.1,
+            wave_count_growth: 0.05,
+            wave_speed_growth: 0.01,
+            runner_wave: 25,
+            brute_wave: 35,
+            initial_money: 500,
+            enemy_kill_reward: 10,
+            bounty_per_wave: 1,
+            wave_bonus: 20,
+            wave_bonus_per_wave: 5,
+            interest_rate: 0.0,
+            interest_cap: 50,
+            early_call_bonus: 2.0,
+            bank_income: 30,
+            initial_lives: 20,
+            difficulty: Difficulty::Normal,
+            mode: GameMode::Classic,
+            seed: rand::random(),
+            effects: true,
+            theme: "pixel".to_string(),
+            keybindings_path: None,
+            waves_path: None,
+            bot: None,
+            headless: false,
+            log_events: false,
+            wave_script: WaveScript::default(),
+        };
+        settings.update_derived();
+        settings
+    }
+
+    /// Builds settings from the defaults, the settings file and the command line, in that order
+    /// of precedence, and checks that the result describes a playable game.
+    pub fn load<I: IntoIterator<Item = String>>(args: I) -> Result<Self, SettingsError> {
+        let overrides = cli::parse_args(args)?;
+
+        let mut settings = Settings::new();
+        let (path, required) = match &overrides.config {
+            Some(path) => (path.clone(), true),
+            None => (PathBuf::from(DEFAULT_SETTINGS_PATH), false),
+        };
+        if let Some(file) = read_toml_file::<file::SettingsFile>(&path, required)? {
+            file.apply(&mut settings);
+        }
+        overrides.apply(&mut settings);
+
+        settings.update_derived();
+        settings.validate()?;
+        settings.wave_script = WaveScript::load(settings.waves_path.as_deref())?;
+        Ok(settings)
+    }
+
+    fn update_derived(&mut self) {
+        self.window_width = (self.grid_width as f32 * self.cell_size).min(MAX_PLAYFIELD_WIDTH);
+        self.window_height =
+            (self.grid_height as f32 * self.cell_size).min(MAX_PLAYFIELD_HEIGHT) + HUD_HEIGHT;
+        self.enemy_radius = self.cell_size * 0.4;
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.grid_width < MIN_GRID_SIZE || self.grid_height < MIN_GRID_SIZE {
+            return Err(SettingsError::Invalid(format!(
+                "grid must be at least {}x{}, got {}x{}",
+                MIN_GRID_SIZE, MIN_GRID_SIZE, self.grid_width, self.grid_height
+            )));
+        }
+        if self.num_waypoints > MAX_WAYPOINTS {
+            return Err(SettingsError::Invalid(format!(
+                "num_waypoints is {}, but paths with more than {} waypoints almost never fit without crossing",
+                self.num_waypoints, MAX_WAYPOINTS
+            )));
+        }
+        // Start, end and every waypoint need a cell of their own, with room left for the path
+        // to wind between them.
+        let cells = self.grid_width * self.grid_height;
+        if (self.num_waypoints + 2) * 8 > cells {
+            return Err(SettingsError::Invalid(format!(
+                "a {}x{} grid is too small for {} waypoints; use a larger grid or fewer waypoints",
+                self.grid_width, self.grid_height, self.num_waypoints
+            )));
+        }
+        if !self.cell_size.is_finite() || self.cell_size < 8.0 {
+            return Err(SettingsError::Invalid(format!(
+                "cell_size must be at least 8 pixels, got {}",
+                self.cell_size
+            )));
+        }
+        if !(self.path_width > 0.0 && self.path_width <= 1.0) {
+            return Err(SettingsError::Invalid(format!(
+                "path_width is a share of the cell size and must be more than 0 and at most 1, got {}",
+                self.path_width
+            )));
+        }
+        ensure_positive("enemy_speed", self.enemy_speed)?;
+        ensure_positive("enemy_health", self.enemy_health)?;
+        ensure_positive("enemy_spawn_interval", self.enemy_spawn_interval)?;
+        if self.enemies_per_wave == 0 || self.waves_to_win == 0 {
+            return Err(SettingsError::Invalid(
+                "enemies_per_wave and waves_to_win must be at least 1".to_string(),
+            ));
+        }
+        if !self.wave_break.is_finite() || self.wave_break < 0.0 {
+            return Err(SettingsError::Invalid(format!(
+                "wave_break must be zero or more seconds, got {}",
+                self.wave_break
+            )));
+        }
+        for (name, value) in [
+            ("wave_health_growth", self.wave_health_growth),
+            ("wave_count_growth", self.wave_count_growth),
+            ("wave_speed_growth", self.wave_speed_growth),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(SettingsError::Invalid(format!("{} must be zero or more, got {}", name, value)));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.interest_rate) {
+            return Err(SettingsError::Invalid(format!(
+                "interest_rate must be between 0 and 1, got {}",
+                self.interest_rate
+            )));
+        }
+        if !self.early_call_bonus.is_finite() || self.early_call_bonus < 0.0 {
+            return Err(SettingsError::Invalid(format!(
+                "early_call_bonus must be zero or more, got {}",
+                self.early_call_bonus
+            )));
+        }
+        if self.initial_lives == 0 {
+            return Err(SettingsError::Invalid("initial_lives must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The wave that wins the game, or `None` in endless mode.
+    pub fn last_wave(&self) -> Option<u32> {
+        match self.mode {
+            GameMode::Classic => Some(self.waves_to_win),
+            GameMode::Endless => None,
+        }
+    }
+
+    /// The initial window size in physical pixels for a monitor of the given physical size.
+    /// The window derived from the grid is scaled by the DPI factor and then shrunk to fit the
+    /// monitor; the playfield is scaled down to match.
+    pub fn initial_window_size(&self, screen_width: f32, screen_height: f32, scale_factor: f32) -> (f32, f32) {
+        let width = (self.window_width * scale_factor).min(screen_width * MAX_SCREEN_SHARE);
+        let height = (self.window_height * scale_factor).min(screen_height * MAX_SCREEN_SHARE);
+        (width.max(MIN_WINDOW_WIDTH), height.max(MIN_WINDOW_HEIGHT))
+    }
+
+    /// Records the new window size and recomputes the HUD scale.
+    pub fn resize_window(&mut self, width: f32, height: f32, scale_factor: f32) {
+        self.window_width = width;
+        self.window_height = height;
+        let logical_height = height / scale_factor;
+        self.ui_scale = scale_factor * (logical_height / REFERENCE_WINDOW_HEIGHT).clamp(MIN_UI_SCALE, 1.0);
+    }
+}
+
+fn ensure_positive(name: &str, value: f32) -> Result<(), SettingsError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(SettingsError::Invalid(format!("{} must be a positive number, got {}", name, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(change: impl FnOnce(&mut Settings)) -> String {
+        let mut settings = Settings::new();
+        change(&mut settings);
+        match settings.validate() {
+            Err(SettingsError::Invalid(message)) => message,
+            Err(err) => panic!("expected invalid settings, got {}", err),
+            Ok(()) => panic!("expected the settings to be rejected"),
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Settings::new().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_small_grids() {
+        assert_eq!(
+            invalid(|s| {
+                s.grid_width = 3;
+                s.grid_height = 20;
+            }),
+            "grid must be at least 4x4, got 3x20"
+        );
+        assert!(invalid(|s| {
+            s.grid_width = 4;
+            s.grid_height = 4;
+        })
+        .contains("too small for 5 waypoints"));
+    }
+
+    #[test]
+    fn rejects_too_many_waypoints() {
+        assert!(invalid(|s| s.num_waypoints = MAX_WAYPOINTS + 1).starts_with("num_waypoints is 11"));
+    }
+
+    #[test]
+    fn rejects_out_of_range_numbers() {
+        assert!(invalid(|s| s.cell_size = 4.0).starts_with("cell_size must be at least 8 pixels"));
+        assert!(invalid(|s| s.path_width = 4.0).starts_with("path_width is a share of the cell size"));
+        assert!(invalid(|s| s.path_width = 0.0).starts_with("path_width"));
+        assert_eq!(invalid(|s| s.enemy_speed = f32::NAN), "enemy_speed must be a positive number, got NaN");
+        assert_eq!(invalid(|s| s.enemy_health = -1.0), "enemy_health must be a positive number, got -1");
+        assert!(invalid(|s| s.waves_to_win = 0).contains("waves_to_win must be at least 1"));
+        assert!(invalid(|s| s.wave_break = -1.0).starts_with("wave_break"));
+        assert!(invalid(|s| s.wave_count_growth = -0.5).starts_with("wave_count_growth"));
+        assert!(invalid(|s| s.interest_rate = 1.5).starts_with("interest_rate"));
+        assert!(invalid(|s| s.early_call_bonus = f32::INFINITY).starts_with("early_call_bonus"));
+        assert_eq!(invalid(|s| s.initial_lives = 0), "initial_lives must be at least 1");
+    }
+}
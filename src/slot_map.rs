@@ -0,0 +1,236 @@
+//! Storage for game entities that hands out stable handles instead of `Vec` indices.
+//!
+//! A handle keeps referring to the same entity while others are added and removed, and stops
+//! resolving once its entity is removed, even after the slot is reused: every slot counts its
+//! occupants, and a handle remembers which occupant it was made for.
+//!
+//! Enemies and towers are kept in one. Shots hit instantly, so there are no projectiles to
+//! keep; they would hold the `Handle` of their target the same way towers do.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// Refers to one entity of a `SlotMap<T>`.
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+// Implemented by hand, since deriving would require the same traits of `T`
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+struct Slot<T> {
+    /// Incremented whenever the slot is emptied.
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Entities addressed by `Handle<T>`. Insertion and removal are O(1); iteration goes in slot
+/// order, which is not insertion order once slots are reused.
+pub struct SlotMap<T> {
+    slots: Vec<Slot<T>>,
+    /// Empty slots, reused last freed first.
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for SlotMap<T> {
+    fn default() -> Self {
+        SlotMap { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+}
+
+impl<T> SlotMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.len += 1;
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].value = Some(value);
+                index
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, value: Some(value) });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        Handle { index, generation: self.slots[index as usize].generation, marker: PhantomData }
+    }
+
+    /// Removes and returns the entity, or `None` if the handle no longer resolves.
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Every entity with its handle.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation, marker: PhantomData };
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> + '_ {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation, marker: PhantomData };
+            slot.value.as_mut().map(|value| (handle, value))
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    /// Removes every entity for which `keep` returns false.
+    pub fn retain(&mut self, mut keep: impl FnMut(Handle<T>, &mut T) -> bool) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let Some(value) = slot.value.as_mut() else {
+                continue;
+            };
+            let handle = Handle { index: index as u32, generation: slot.generation, marker: PhantomData };
+            if !keep(handle, value) {
+                slot.value = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+                self.len -= 1;
+            }
+        }
+    }
+}
+
+/// Panics if the handle no longer resolves.
+impl<T> Index<Handle<T>> for SlotMap<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle<T>) -> &T {
+        self.get(handle).expect("handle refers to a removed entity")
+    }
+}
+
+impl<T> IndexMut<Handle<T>> for SlotMap<T> {
+    fn index_mut(&mut self, handle: Handle<T>) -> &mut T {
+        self.get_mut(handle).expect("handle refers to a removed entity")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_handle_stops_resolving() {
+        let mut map = SlotMap::new();
+        let first = map.insert("first");
+        let second = map.insert("second");
+
+        assert_eq!(map.remove(first), Some("first"));
+        assert_eq!(map.get(first), None);
+        assert!(!map.contains(first));
+        assert_eq!(map.remove(first), None);
+        assert_eq!(map.get(second), Some(&"second"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn reused_slot_does_not_resolve_stale_handle() {
+        let mut map = SlotMap::new();
+        let stale = map.insert(1);
+        map.remove(stale);
+        let fresh = map.insert(2);
+
+        // Same slot, next occupant
+        assert_eq!(fresh.index, stale.index);
+        assert_ne!(fresh, stale);
+        assert_eq!(map.get(stale), None);
+        assert_eq!(map.get_mut(stale), None);
+        assert_eq!(map.remove(stale), None);
+        assert_eq!(map[fresh], 2);
+    }
+
+    #[test]
+    fn iter_mut_visits_live_entities() {
+        let mut map = SlotMap::new();
+        let handles: Vec<_> = (0..4).map(|value| map.insert(value)).collect();
+        map.remove(handles[1]);
+
+        let mut visited = Vec::new();
+        for (handle, value) in map.iter_mut() {
+            *value += 10;
+            visited.push(handle);
+        }
+
+        assert_eq!(visited, vec![handles[0], handles[2], handles[3]]);
+        assert_eq!((map[handles[0]], map[handles[2]], map[handles[3]]), (10, 12, 13));
+    }
+}
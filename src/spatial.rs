@@ -0,0 +1,170 @@
+//! Uniform grid of enemy positions for range queries that only look at nearby enemies.
+
+use crate::entities::{EnemyId, Grunt};
+use crate::slot_map::SlotMap;
+
+/// Side of a bucket, in cells. Close to the shortest tower range, so a query covers few
+/// buckets without each bucket holding too many enemies.
+const BUCKET_SIZE: f32 = 2.0;
+
+/// Enemies bucketed by position. Rebuilt every tick, as enemies move.
+pub struct SpatialHash {
+    columns: usize,
+    rows: usize,
+    /// Start of every bucket's entries in `entries`, plus the end of the last one.
+    bucket_starts: Vec<usize>,
+    /// Enemies and their positions, grouped by bucket.
+    entries: Vec<(EnemyId, (f32, f32))>,
+    /// Scratch space of `rebuild`, kept to avoid allocating every tick.
+    placed: Vec<(usize, EnemyId, (f32, f32))>,
+    cursors: Vec<usize>,
+}
+
+impl SpatialHash {
+    /// An empty hash covering a `width` by `height` cell level.
+    pub fn new(width: usize, height: usize) -> Self {
+        let columns = (width as f32 / BUCKET_SIZE).ceil().max(1.0) as usize;
+        let rows = (height as f32 / BUCKET_SIZE).ceil().max(1.0) as usize;
+        SpatialHash {
+            columns,
+            rows,
+            bucket_starts: vec![0; columns * rows + 1],
+            entries: Vec::new(),
+            placed: Vec::new(),
+            cursors: Vec::new(),
+        }
+    }
+
+    /// Replaces the contents with the given enemies. Enemies without a finite position are
+    /// left out, since no range can contain them.
+    pub fn rebuild(&mut self, enemies: &SlotMap<Grunt>) {
+        self.bucket_starts.iter_mut().for_each(|start| *start = 0);
+
+        // Counting sort: count per bucket, turn counts into starts, then place the entries
+        self.placed.clear();
+        for (handle, enemy) in enemies.iter() {
+            let (x, y) = enemy.position;
+            if x.is_finite() && y.is_finite() {
+                let bucket = self.bucket(x, y);
+                self.bucket_starts[bucket + 1] += 1;
+                self.placed.push((bucket, handle, enemy.position));
+            }
+        }
+        for bucket in 0..self.columns * self.rows {
+            self.bucket_starts[bucket + 1] += self.bucket_starts[bucket];
+        }
+        // Sized with the entries in any order; every one is overwritten below
+        self.entries.clear();
+        self.entries.extend(self.placed.iter().map(|&(_, handle, position)| (handle, position)));
+        self.cursors.clone_from(&self.bucket_starts);
+        for &(bucket, handle, position) in &self.placed {
+            self.entries[self.cursors[bucket]] = (handle, position);
+            self.cursors[bucket] += 1;
+        }
+    }
+
+    /// Adds every enemy within `radius` of `center` to `out`.
+    pub fn query(&self, center: (f32, f32), radius: f32, out: &mut Vec<EnemyId>) {
+        if !(center.0.is_finite() && center.1.is_finite() && radius >= 0.0) {
+            return;
+        }
+        let (first_column, first_row) = self.column_row(center.0 - radius, center.1 - radius);
+        let (last_column, last_row) = self.column_row(center.0 + radius, center.1 + radius);
+        let radius_squared = radius * radius;
+        for row in first_row..=last_row {
+            let first = self.bucket_starts[row * self.columns + first_column];
+            let last = self.bucket_starts[row * self.columns + last_column + 1];
+            for &(handle, (x, y)) in &self.entries[first..last] {
+                let dx = x - center.0;
+                let dy = y - center.1;
+                if dx * dx + dy * dy <= radius_squared {
+                    out.push(handle);
+                }
+            }
+        }
+    }
+
+    fn column_row(&self, x: f32, y: f32) -> (usize, usize) {
+        // Casts saturate, so positions off the level land in the edge buckets
+        let column = ((x / BUCKET_SIZE) as usize).min(self.columns - 1);
+        let row = ((y / BUCKET_SIZE) as usize).min(self.rows - 1);
+        (column, row)
+    }
+
+    fn bucket(&self, x: f32, y: f32) -> usize {
+        let (column, row) = self.column_row(x, y);
+        row * self.columns + column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::level::Point;
+    use crate::waves::EnemyKind;
+
+    fn enemy_at(position: (f32, f32)) -> Grunt {
+        let mut enemy = Grunt::new(0, EnemyKind::Grunt, Point { x: 0, y: 0 }, 100.0, 1.0, 1, 1);
+        enemy.position = position;
+        enemy
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (width, height) = (23, 17);
+        let mut enemies = SlotMap::new();
+        // Some enemies off the level, as the edge buckets take those
+        for _ in 0..300 {
+            let x = rng.gen_range(-3.0..width as f32 + 3.0);
+            let y = rng.gen_range(-3.0..height as f32 + 3.0);
+            enemies.insert(enemy_at((x, y)));
+        }
+        let mut hash = SpatialHash::new(width, height);
+        hash.rebuild(&enemies);
+
+        let mut found = Vec::new();
+        for _ in 0..200 {
+            let center = (rng.gen_range(-2.0..width as f32 + 2.0), rng.gen_range(-2.0..height as f32 + 2.0));
+            let radius = rng.gen_range(0.0..6.0);
+            found.clear();
+            hash.query(center, radius, &mut found);
+
+            let expected: HashSet<EnemyId> = enemies
+                .iter()
+                .filter(|(_, enemy)| {
+                    let dx = enemy.position.0 - center.0;
+                    let dy = enemy.position.1 - center.1;
+                    dx * dx + dy * dy <= radius * radius
+                })
+                .map(|(handle, _)| handle)
+                .collect();
+            assert_eq!(found.len(), expected.len(), "duplicates or misses around {:?}", center);
+            assert_eq!(found.iter().copied().collect::<HashSet<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn ignores_nan_positions() {
+        let mut enemies = SlotMap::new();
+        let inside = enemies.insert(enemy_at((2.5, 2.5)));
+        enemies.insert(enemy_at((f32::NAN, 2.5)));
+        enemies.insert(enemy_at((2.5, f32::NAN)));
+        let mut hash = SpatialHash::new(10, 10);
+        hash.rebuild(&enemies);
+
+        let mut found = Vec::new();
+        hash.query((2.0, 2.0), 100.0, &mut found);
+        assert_eq!(found, vec![inside]);
+
+        found.clear();
+        hash.query((f32::NAN, 2.0), 100.0, &mut found);
+        hash.query((2.0, 2.0), f32::NAN, &mut found);
+        assert!(found.is_empty());
+    }
+}
@@ -1,6 +1,17 @@
-use ggez::graphics::Color;
+use serde::Serialize;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 use std::time::Duration;
-use crate::entities::Grunt;
+use crate::entities::{EnemyId, Grunt};
+use crate::slot_map::{Handle, SlotMap};
+use crate::spatial::SpatialHash;
+
+pub const MAX_TOWER_LEVEL: u32 = 3;
+/// Fraction of the money spent on a tower that is returned when it is sold.
+pub const SELL_REFUND: f32 = 0.7;
+/// Largest angle, in radians, between a turret and its target at which the tower fires.
+pub const AIM_TOLERANCE: f32 = 0.1;
+
+pub type TowerId = Handle<Tower>;
 
 pub struct Tower {
     pub position: (usize, usize),
@@ -8,15 +19,99 @@ pub struct Tower {
     pub range: f32,
     pub damage: f32,
     pub fire_rate: f32,
-    pub color: Color,
+    /// Radians per second the turret turns.
+    pub turn_rate: f32,
+    /// Direction the turret points, in radians clockwise from east, between -π and π.
+    pub facing: f32,
     pub last_fire_time: Duration,
+    /// When the tower last shot. `last_fire_time` also moves on while there is nothing to
+    /// shoot at; this does not.
+    pub last_shot_time: Option<Duration>,
+    /// The enemy the turret follows while reloading: the best target when the tower last
+    /// looked for one.
+    pub tracking: Option<EnemyId>,
+    pub level: u32,
+    pub targeting: TargetingMode,
+    pub total_cost: u32,
+    /// Index of the tower's record in `GameStats::towers`.
+    pub stats_id: usize,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TowerType {
     Gun,
     Sniper,
     Flame,
+    /// Does not attack; pays income at the end of every wave.
+    Bank,
+}
+
+/// Which enemies in range a tower prefers to shoot at.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TargetingMode {
+    Closest,
+    First,
+    Last,
+    Strongest,
+    Weakest,
+}
+
+impl TowerType {
+    pub const ALL: [TowerType; 4] = [TowerType::Gun, TowerType::Sniper, TowerType::Flame, TowerType::Bank];
+
+    pub fn cost(self) -> u32 {
+        match self {
+            TowerType::Gun => 100,
+            TowerType::Sniper => 150,
+            TowerType::Flame => 200,
+            TowerType::Bank => 250,
+        }
+    }
+
+    pub fn attacks(self) -> bool {
+        !matches!(self, TowerType::Bank)
+    }
+
+    /// Enemies hit by one shot.
+    pub fn max_targets(self) -> usize {
+        match self {
+            TowerType::Gun | TowerType::Sniper => 1,
+            TowerType::Flame => 3,
+            TowerType::Bank => 0,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TowerType::Gun => "Gun Tower",
+            TowerType::Sniper => "Sniper Tower",
+            TowerType::Flame => "Flame Tower",
+            TowerType::Bank => "Bank",
+        }
+    }
+}
+
+impl TargetingMode {
+    pub fn next(self) -> Self {
+        match self {
+            TargetingMode::Closest => TargetingMode::First,
+            TargetingMode::First => TargetingMode::Last,
+            TargetingMode::Last => TargetingMode::Strongest,
+            TargetingMode::Strongest => TargetingMode::Weakest,
+            TargetingMode::Weakest => TargetingMode::Closest,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TargetingMode::Closest => "Closest",
+            TargetingMode::First => "First",
+            TargetingMode::Last => "Last",
+            TargetingMode::Strongest => "Strongest",
+            TargetingMode::Weakest => "Weakest",
+        }
+    }
 }
 
 impl Tower {
@@ -28,8 +123,15 @@ impl Tower {
                 range: 1.5,
                 damage: 10.0,
                 fire_rate: 1.0,
-                color: Color::BLUE,
+                turn_rate: TAU,
+                facing: 0.0,
                 last_fire_time: Duration::from_secs(0),
+                last_shot_time: None,
+                tracking: None,
+                level: 1,
+                targeting: TargetingMode::Closest,
+                total_cost: tower_type.cost(),
+                stats_id: 0,
             },
             TowerType::Sniper => Tower {
                 position,
@@ -37,8 +139,15 @@ impl Tower {
                 range: 4.5,
                 damage: 50.0,
                 fire_rate: 0.5,
-                color: Color::RED,
+                turn_rate: FRAC_PI_2,
+                facing: 0.0,
                 last_fire_time: Duration::from_secs(0),
+                last_shot_time: None,
+                tracking: None,
+                level: 1,
+                targeting: TargetingMode::Closest,
+                total_cost: tower_type.cost(),
+                stats_id: 0,
             },
             TowerType::Flame => Tower {
                 position,
@@ -46,43 +155,179 @@ impl Tower {
                 range: 2.5,
                 damage: 5.0,
                 fire_rate: 2.0,
-                color: Color::YELLOW,
+                turn_rate: PI,
+                facing: 0.0,
                 last_fire_time: Duration::from_secs(0),
+                last_shot_time: None,
+                tracking: None,
+                level: 1,
+                targeting: TargetingMode::Closest,
+                total_cost: tower_type.cost(),
+                stats_id: 0,
             },
+            TowerType::Bank => Tower {
+                position,
+                tower_type,
+                range: 0.0,
+                damage: 0.0,
+                fire_rate: 0.0,
+                turn_rate: 0.0,
+                facing: 0.0,
+                last_fire_time: Duration::from_secs(0),
+                last_shot_time: None,
+                tracking: None,
+                level: 1,
+                targeting: TargetingMode::Closest,
+                total_cost: tower_type.cost(),
+                stats_id: 0,
+            },
+        }
+    }
+
+    /// Cost of the next upgrade, or `None` at the maximum level.
+    pub fn upgrade_cost(&self) -> Option<u32> {
+        if self.level < MAX_TOWER_LEVEL {
+            Some(self.tower_type.cost() * self.level)
+        } else {
+            None
+        }
+    }
+
+    pub fn upgrade(&mut self) {
+        if let Some(cost) = self.upgrade_cost() {
+            self.level += 1;
+            self.total_cost += cost;
+            if self.tower_type.attacks() {
+                self.damage *= 1.5;
+                self.range += 0.5;
+                self.fire_rate *= 1.15;
+                self.turn_rate *= 1.15;
+            }
         }
     }
 
+    pub fn sell_value(&self) -> u32 {
+        (self.total_cost as f32 * SELL_REFUND) as u32
+    }
+
+    /// Turns the turret towards `target`, a position in cells, as far as the turn rate allows
+    /// in `delta_time` seconds. Returns whether it then points at the target closely enough to
+    /// fire.
+    pub fn aim_at(&mut self, target: (f32, f32), delta_time: f32) -> bool {
+        let wanted = (target.1 - self.position.1 as f32 - 0.5).atan2(target.0 - self.position.0 as f32 - 0.5);
+        if !wanted.is_finite() {
+            return false;
+        }
+        let turn = wrap_angle(wanted - self.facing);
+        let step = self.turn_rate * delta_time;
+        self.facing = if turn.abs() <= step { wanted } else { wrap_angle(self.facing + step * turn.signum()) };
+        wrap_angle(wanted - self.facing).abs() <= AIM_TOLERANCE
+    }
+
+    /// Whether an enemy at `position`, in cells, is within range.
+    pub fn in_range(&self, position: (f32, f32)) -> bool {
+        let dx = position.0 - self.position.0 as f32 - 0.5;
+        let dy = position.1 - self.position.1 as f32 - 0.5;
+        dx * dx + dy * dy <= self.range * self.range
+    }
+
     pub fn can_fire(&self, current_time: Duration) -> bool {
-        current_time.as_secs_f32() - self.last_fire_time.as_secs_f32() >= 1.0 / self.fire_rate
+        self.tower_type.attacks()
+            && current_time.as_secs_f32() - self.last_fire_time.as_secs_f32() >= 1.0 / self.fire_rate
     }
 
-    pub fn find_targets(&self, enemies: &[Grunt]) -> Vec<usize> {
-        let mut targets = Vec::new();
+    /// The enemies the tower shoots at, best first for its targeting mode. `grid` must have
+    /// been rebuilt from `enemies` since they last moved.
+    pub fn find_targets(&self, enemies: &SlotMap<Grunt>, grid: &SpatialHash) -> Vec<EnemyId> {
         let tower_pos = (self.position.0 as f32 + 0.5, self.position.1 as f32 + 0.5);
+        let mut in_range = Vec::new();
+        grid.query(tower_pos, self.range, &mut in_range);
 
-        for (index, enemy) in enemies.iter().enumerate() {
-            let dx = tower_pos.0 - enemy.position.0;
-            let dy = tower_pos.1 - enemy.position.1;
-            let distance = (dx * dx + dy * dy).sqrt();
+        // Lower keys are preferred. Each is computed once, and a NaN key (say from a NaN
+        // health) ranks last instead of breaking the sort.
+        let mut candidates: Vec<(f32, u32, EnemyId)> = in_range
+            .into_iter()
+            .filter_map(|handle| enemies.get(handle).map(|enemy| (handle, enemy)))
+            .map(|(handle, enemy)| {
+                let key = match self.targeting {
+                    TargetingMode::Closest => {
+                        (tower_pos.0 - enemy.position.0).powi(2) + (tower_pos.1 - enemy.position.1).powi(2)
+                    }
+                    TargetingMode::First => -enemy.progress(),
+                    TargetingMode::Last => enemy.progress(),
+                    TargetingMode::Strongest => -enemy.health,
+                    TargetingMode::Weakest => enemy.health,
+                };
+                (if key.is_nan() { f32::INFINITY } else { key }, enemy.id, handle)
+            })
+            .collect();
 
-            if distance <= self.range {
-                targets.push(index);
-            }
+        // Ties go to the enemy spawned first
+        let order = |a: &(f32, u32, EnemyId), b: &(f32, u32, EnemyId)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+        let max_targets = self.tower_type.max_targets();
+        if candidates.len() > max_targets {
+            candidates.select_nth_unstable_by(max_targets, order);
+            candidates.truncate(max_targets);
         }
+        candidates.sort_unstable_by(order);
 
-        targets.sort_by(|&a, &b| {
-            let dist_a = (tower_pos.0 - enemies[a].position.0).powi(2) +
-                         (tower_pos.1 - enemies[a].position.1).powi(2);
-            let dist_b = (tower_pos.0 - enemies[b].position.0).powi(2) +
-                         (tower_pos.1 - enemies[b].position.1).powi(2);
-            dist_a.partial_cmp(&dist_b).unwrap()
-        });
-
-        match self.tower_type {
-            TowerType::Gun | TowerType::Sniper => targets.truncate(1),
-            TowerType::Flame => targets.truncate(3),
-        }
+        candidates.into_iter().map(|(_, _, handle)| handle).collect()
+    }
+}
+
+/// The same angle between -π and π.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn wrap_angle_stays_between_minus_pi_and_pi() {
+        assert!((wrap_angle(0.5) - 0.5).abs() < EPSILON);
+        assert!((wrap_angle(PI + 0.5) - (-PI + 0.5)).abs() < EPSILON);
+        assert!((wrap_angle(-PI - 0.5) - (PI - 0.5)).abs() < EPSILON);
+        assert!((wrap_angle(3.0 * TAU + 1.0) - 1.0).abs() < EPSILON);
+        assert!((wrap_angle(-3.0 * TAU - 1.0) - -1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn aim_turns_the_short_way_across_pi() {
+        // Tower at (0, 0), centered on (0.5, 0.5); the target is just past west, clockwise
+        let mut tower = Tower::new((0, 0), TowerType::Gun);
+        tower.facing = PI - 0.2;
+        tower.turn_rate = 1.0;
+        let target = (0.5 + (PI + 0.2).cos(), 0.5 + (PI + 0.2).sin());
+
+        // Turning clockwise crosses π and wraps to -π; the long way round would take seconds
+        let aimed = tower.aim_at(target, 0.35);
+        assert!((tower.facing - wrap_angle(PI + 0.15)).abs() < EPSILON, "facing {}", tower.facing);
+        assert!(aimed, "within AIM_TOLERANCE after the step");
+    }
 
-        targets
+    #[test]
+    fn aim_stops_on_the_target_instead_of_overshooting() {
+        let mut tower = Tower::new((0, 0), TowerType::Gun);
+        tower.facing = 0.0;
+        tower.turn_rate = 10.0;
+        // Straight south of the tower's center: a quarter turn clockwise
+        let aimed = tower.aim_at((0.5, 3.0), 1.0);
+        assert!((tower.facing - FRAC_PI_2).abs() < EPSILON, "facing {}", tower.facing);
+        assert!(aimed);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn aim_is_limited_by_the_turn_rate() {
+        let mut tower = Tower::new((0, 0), TowerType::Sniper);
+        tower.facing = 0.0;
+        tower.turn_rate = 1.0;
+        // Due west, half a turn away: either way round, only 0.5 radians in half a second
+        let aimed = tower.aim_at((-3.0, 0.5), 0.5);
+        assert!((tower.facing.abs() - 0.5).abs() < EPSILON, "facing {}", tower.facing);
+        assert!(!aimed);
+    }
+}
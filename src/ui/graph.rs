@@ -0,0 +1,109 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect, Text, TextFragment};
+
+use crate::economy::{Economy, Income};
+
+const TITLE_SIZE: f32 = 24.0;
+const TEXT_SIZE: f32 = 18.0;
+const LINE_HEIGHT: f32 = 24.0;
+const PADDING: f32 = 12.0;
+const LINE_COLOR: Color = Color::new(0.95, 0.8, 0.25, 1.0);
+const LABEL_COLOR: Color = Color::new(0.75, 0.75, 0.75, 1.0);
+
+/// Draws the money history as a step graph in the top of `area`, with the total from each
+/// source of income listed below it.
+pub fn render_money_graph(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    area: Rect,
+    economy: &Economy,
+    scale: f32,
+) -> GameResult {
+    let padding = PADDING * scale;
+    let line_height = LINE_HEIGHT * scale;
+    let legend_height = (Income::ALL.len() + 1) as f32 * line_height;
+
+    let title = Text::new(TextFragment::new("Money").scale(TITLE_SIZE * scale));
+    canvas.draw(&title, graphics::DrawParam::default().dest([area.x, area.y]).color(Color::WHITE));
+
+    let plot = Rect::new(
+        area.x,
+        area.y + line_height + padding,
+        area.w,
+        (area.h - 2.0 * line_height - legend_height - 2.0 * padding).max(0.0),
+    );
+    if plot.h >= line_height && plot.w >= line_height {
+        render_plot(ctx, canvas, plot, economy.history(), scale)?;
+    }
+
+    let mut y = plot.bottom() + line_height + padding;
+    for source in Income::ALL {
+        let line = format!("{:<14}${}", source.name(), economy.earned_from(source));
+        let text = Text::new(TextFragment::new(line).scale(TEXT_SIZE * scale));
+        canvas.draw(&text, graphics::DrawParam::default().dest([area.x, y]).color(LABEL_COLOR));
+        y += line_height;
+    }
+    let spent = format!("{:<14}${}", "Spent", economy.total_spent());
+    let text = Text::new(TextFragment::new(spent).scale(TEXT_SIZE * scale));
+    canvas.draw(&text, graphics::DrawParam::default().dest([area.x, y]).color(LABEL_COLOR));
+
+    Ok(())
+}
+
+fn render_plot(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    plot: Rect,
+    history: &[(f32, u32)],
+    scale: f32,
+) -> GameResult {
+    let background = Mesh::new_rectangle(ctx, DrawMode::fill(), plot, Color::new(0.1, 0.1, 0.12, 1.0))?;
+    canvas.draw(&background, graphics::DrawParam::default());
+    let border = Mesh::new_rectangle(ctx, DrawMode::stroke(1.0), plot, LABEL_COLOR)?;
+    canvas.draw(&border, graphics::DrawParam::default());
+
+    let duration = history.last().map_or(0.0, |&(time, _)| time).max(1.0);
+    let max_money = history.iter().map(|&(_, money)| money).max().unwrap_or(0).max(1) as f32;
+    let to_screen = |time: f32, money: u32| {
+        [
+            plot.x + time / duration * plot.w,
+            plot.bottom() - money as f32 / max_money * plot.h,
+        ]
+    };
+
+    // Money changes in steps, so hold each value until the next sample
+    let mut points = Vec::with_capacity(history.len() * 2);
+    for (index, &(time, money)) in history.iter().enumerate() {
+        if index > 0 {
+            points.push(to_screen(time, history[index - 1].1));
+        }
+        points.push(to_screen(time, money));
+    }
+    if let Some(&(time, money)) = history.last() {
+        if time < duration {
+            points.push(to_screen(duration, money));
+        }
+    }
+    points.dedup();
+    if points.len() >= 2 {
+        let line = Mesh::new_line(ctx, &points, 2.0 * scale, LINE_COLOR)?;
+        canvas.draw(&line, graphics::DrawParam::default());
+    }
+
+    let labels = [
+        (format!("${}", max_money as u32), [plot.x + 4.0 * scale, plot.y + 2.0 * scale]),
+        (format!("{:.0}s", duration), [plot.right(), plot.bottom() + 2.0 * scale]),
+    ];
+    for (index, (label, position)) in labels.into_iter().enumerate() {
+        let text = Text::new(TextFragment::new(label).scale(TEXT_SIZE * scale));
+        let offset = if index == 1 { text.measure(ctx)?.x } else { 0.0 };
+        canvas.draw(
+            &text,
+            graphics::DrawParam::default()
+                .dest([position[0] - offset, position[1]])
+                .color(LABEL_COLOR),
+        );
+    }
+
+    Ok(())
+}
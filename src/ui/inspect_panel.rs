@@ -0,0 +1,113 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect, Text, TextFragment};
+
+use crate::game_controller::GameController;
+use crate::rendering::Selection;
+use crate::towers::MAX_TOWER_LEVEL;
+
+use super::layout::Layout;
+
+const TITLE_SIZE: f32 = 28.0;
+const TEXT_SIZE: f32 = 22.0;
+const LINE_HEIGHT: f32 = 30.0;
+const PADDING: f32 = 14.0;
+
+/// Draws the details of the selected tower or enemy at the top of the side panel. Values are
+/// read from the game every frame, so they update while the panel is open.
+pub fn render_inspect_panel(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    game_controller: &GameController,
+    layout: &Layout,
+    selection: Selection,
+) -> GameResult {
+    let Some((title, lines)) = describe(game_controller, selection) else {
+        return Ok(());
+    };
+
+    let scale = layout.scale;
+    let panel = layout.side_panel();
+    let height = (2.0 * PADDING + LINE_HEIGHT * (lines.len() + 1) as f32) * scale;
+    let background = Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(panel.x, panel.y, panel.w, height.min(panel.h)),
+        Color::new(0.15, 0.15, 0.18, 0.92),
+    )?;
+    canvas.draw(&background, graphics::DrawParam::default());
+
+    let x = panel.x + PADDING * scale;
+    let mut y = panel.y + PADDING * scale;
+    let title = Text::new(TextFragment::new(title).scale(TITLE_SIZE * scale));
+    canvas.draw(&title, graphics::DrawParam::default().dest([x, y]).color(Color::WHITE));
+    y += LINE_HEIGHT * scale;
+
+    for line in lines {
+        let text = Text::new(TextFragment::new(line).scale(TEXT_SIZE * scale));
+        canvas.draw(
+            &text,
+            graphics::DrawParam::default()
+                .dest([x, y])
+                .color(Color::new(0.85, 0.85, 0.85, 1.0)),
+        );
+        y += LINE_HEIGHT * scale;
+    }
+
+    Ok(())
+}
+
+/// Panel title and lines for the selection, or `None` once it no longer exists.
+fn describe(
+    game_controller: &GameController,
+    selection: Selection,
+) -> Option<(String, Vec<String>)> {
+    match selection {
+        Selection::Tower(position) => {
+            let tower = &game_controller.towers[game_controller.tower_at(position)?];
+            let record = &game_controller.game_stats.towers[tower.stats_id];
+            let upgrade = match tower.upgrade_cost() {
+                Some(cost) => format!("Upgrade: ${}", cost),
+                None => "Upgrade: max level".to_string(),
+            };
+            if !tower.tower_type.attacks() {
+                let income = game_controller.game_stats.economy.rules.bank_income(tower.level);
+                return Some((
+                    tower.tower_type.name().to_string(),
+                    vec![
+                        format!("Level: {}/{}", tower.level, MAX_TOWER_LEVEL),
+                        format!("Income: ${} per wave", income),
+                        format!("Money earned: ${}", record.money_earned),
+                        format!("Sell value: ${}", tower.sell_value()),
+                        upgrade,
+                    ],
+                ));
+            }
+            Some((
+                tower.tower_type.name().to_string(),
+                vec![
+                    format!("Level: {}/{}", tower.level, MAX_TOWER_LEVEL),
+                    format!("Range: {:.1} cells", tower.range),
+                    format!("Damage: {:.1}", tower.damage),
+                    format!("Fire rate: {:.2}/s", tower.fire_rate),
+                    format!("Turn rate: {:.0}°/s", tower.turn_rate.to_degrees()),
+                    format!("Targeting: {}", tower.targeting.name()),
+                    format!("Damage dealt: {:.0}", record.damage_dealt),
+                    format!("Kills: {}", record.kills),
+                    format!("Sell value: ${}", tower.sell_value()),
+                    upgrade,
+                ],
+            ))
+        }
+        Selection::Enemy(handle) => {
+            let enemy = game_controller.enemy(handle)?;
+            Some((
+                format!("{} #{}", enemy.kind.name(), enemy.id),
+                vec![
+                    format!("Health: {:.0}/{:.0}", enemy.health.max(0.0), enemy.health_bar.max_health),
+                    format!("Speed: {:.1} cells/s", enemy.speed),
+                    format!("Bounty: ${}", enemy.bounty),
+                ],
+            ))
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use ggez::graphics::Rect;
+
+use crate::settings::{Settings, HUD_HEIGHT};
+
+/// Simulation speed multipliers, one HUD button each.
+pub const GAME_SPEEDS: [f32; 3] = [1.0, 2.0, 3.0];
+const SPEED_BUTTON_WIDTH: f32 = 56.0;
+const SPEED_BUTTON_MARGIN: f32 = 4.0;
+const CALL_BUTTON_WIDTH: f32 = 150.0;
+const SIDE_PANEL_WIDTH: f32 = 300.0;
+
+/// Screen regions of the playing scene in physical pixels: the HUD along the top of the
+/// window and the playfield below it. Rendering and input both go through this so they
+/// always agree on where things are.
+#[derive(Clone, Copy)]
+pub struct Layout {
+    pub hud: Rect,
+    pub playfield: Rect,
+    pub scale: f32,
+}
+
+impl Layout {
+    pub fn new(settings: &Settings) -> Self {
+        let scale = settings.ui_scale;
+        let hud_height = (HUD_HEIGHT * scale).min(settings.window_height - 1.0);
+        Layout {
+            hud: Rect::new(0.0, 0.0, settings.window_width, hud_height),
+            playfield: Rect::new(
+                0.0,
+                hud_height,
+                settings.window_width,
+                (settings.window_height - hud_height).max(1.0),
+            ),
+            scale,
+        }
+    }
+
+    /// Button for `GAME_SPEEDS[index]`, right-aligned in the HUD.
+    pub fn speed_button(&self, index: usize) -> Rect {
+        let width = SPEED_BUTTON_WIDTH * self.scale;
+        let margin = SPEED_BUTTON_MARGIN * self.scale;
+        let from_right = (GAME_SPEEDS.len() - index) as f32;
+        Rect::new(
+            self.hud.right() - from_right * (width + margin),
+            self.hud.y + margin,
+            width,
+            self.hud.h - 2.0 * margin,
+        )
+    }
+
+    pub fn speed_button_at(&self, x: f32, y: f32) -> Option<usize> {
+        (0..GAME_SPEEDS.len()).find(|&index| self.speed_button(index).contains([x, y]))
+    }
+
+    /// Button that calls the next wave early, left of the speed buttons.
+    pub fn call_wave_button(&self) -> Rect {
+        let speed_button = self.speed_button(0);
+        let margin = SPEED_BUTTON_MARGIN * self.scale;
+        let width = CALL_BUTTON_WIDTH * self.scale;
+        Rect::new(speed_button.x - margin - width, speed_button.y, width, speed_button.h)
+    }
+
+    /// Inspection panel along the right edge of the playfield, drawn over it while something
+    /// is selected.
+    pub fn side_panel(&self) -> Rect {
+        let width = (SIDE_PANEL_WIDTH * self.scale).min(self.playfield.w);
+        Rect::new(self.playfield.right() - width, self.playfield.y, width, self.playfield.h)
+    }
+
+    /// Part of the HUD left of the buttons, where the stats are listed.
+    pub fn stats_area(&self) -> Rect {
+        let buttons_left = self.call_wave_button().x;
+        Rect::new(self.hud.x, self.hud.y, (buttons_left - self.hud.x).max(0.0), self.hud.h)
+    }
+}
@@ -0,0 +1,121 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect, Text, TextFragment};
+
+use crate::settings::Settings;
+
+const ITEM_WIDTH: f32 = 360.0;
+const ITEM_HEIGHT: f32 = 56.0;
+const ITEM_SPACING: f32 = 16.0;
+const TITLE_HEIGHT: f32 = 120.0;
+const LINE_HEIGHT: f32 = 32.0;
+
+/// A vertical list of buttons with a title and optional lines of text, centered in the window.
+pub struct Menu {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub items: Vec<String>,
+}
+
+impl Menu {
+    pub fn new(title: impl Into<String>, items: Vec<String>) -> Self {
+        Menu {
+            title: title.into(),
+            lines: Vec::new(),
+            items,
+        }
+    }
+
+    pub fn with_lines(mut self, lines: Vec<String>) -> Self {
+        self.lines = lines;
+        self
+    }
+
+    fn header_height(&self, scale: f32) -> f32 {
+        (TITLE_HEIGHT + self.lines.len() as f32 * LINE_HEIGHT) * scale
+    }
+
+    fn top(&self, area: Rect, scale: f32) -> f32 {
+        let height = self.header_height(scale) + self.items.len() as f32 * (ITEM_HEIGHT + ITEM_SPACING) * scale;
+        area.y + ((area.h - height) / 2.0).max(0.0)
+    }
+
+    fn item_rect(&self, index: usize, area: Rect, scale: f32) -> Rect {
+        Rect::new(
+            area.x + (area.w - ITEM_WIDTH * scale) / 2.0,
+            self.top(area, scale) + self.header_height(scale) + index as f32 * (ITEM_HEIGHT + ITEM_SPACING) * scale,
+            ITEM_WIDTH * scale,
+            ITEM_HEIGHT * scale,
+        )
+    }
+
+    pub fn item_at(&self, x: f32, y: f32, settings: &Settings) -> Option<usize> {
+        self.item_at_in(x, y, window_area(settings), settings.ui_scale)
+    }
+
+    /// Like `item_at`, for a menu centered in `area` instead of the window.
+    pub fn item_at_in(&self, x: f32, y: f32, area: Rect, scale: f32) -> Option<usize> {
+        (0..self.items.len()).find(|&index| self.item_rect(index, area, scale).contains([x, y]))
+    }
+
+    pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas, settings: &Settings) -> GameResult {
+        self.draw_in(ctx, canvas, window_area(settings), settings.ui_scale)
+    }
+
+    /// Draws the menu centered in `area` instead of the window.
+    pub fn draw_in(&self, ctx: &mut Context, canvas: &mut Canvas, area: Rect, scale: f32) -> GameResult {
+        let title = Text::new(TextFragment::new(self.title.as_str()).scale(56.0 * scale));
+        let title_dims = title.measure(ctx)?;
+        canvas.draw(
+            &title,
+            graphics::DrawParam::default()
+                .dest([area.x + (area.w - title_dims.x) / 2.0, self.top(area, scale)])
+                .color(Color::WHITE),
+        );
+
+        for (index, line) in self.lines.iter().enumerate() {
+            let text = Text::new(TextFragment::new(line.as_str()).scale(24.0 * scale));
+            let text_dims = text.measure(ctx)?;
+            canvas.draw(
+                &text,
+                graphics::DrawParam::default()
+                    .dest([
+                        area.x + (area.w - text_dims.x) / 2.0,
+                        self.top(area, scale) + (TITLE_HEIGHT + index as f32 * LINE_HEIGHT) * scale,
+                    ])
+                    .color(Color::WHITE),
+            );
+        }
+
+        let mouse = ctx.mouse.position();
+        let hovered = self.item_at_in(mouse.x, mouse.y, area, scale);
+
+        for (index, label) in self.items.iter().enumerate() {
+            let rect = self.item_rect(index, area, scale);
+            let color = if hovered == Some(index) {
+                Color::new(0.45, 0.45, 0.55, 1.0)
+            } else {
+                Color::new(0.25, 0.25, 0.3, 1.0)
+            };
+            let button = Mesh::new_rectangle(ctx, DrawMode::fill(), rect, color)?;
+            canvas.draw(&button, graphics::DrawParam::default());
+
+            let text = Text::new(TextFragment::new(label.as_str()).scale(28.0 * scale));
+            let text_dims = text.measure(ctx)?;
+            canvas.draw(
+                &text,
+                graphics::DrawParam::default()
+                    .dest([
+                        rect.x + (rect.w - text_dims.x) / 2.0,
+                        rect.y + (rect.h - text_dims.y) / 2.0,
+                    ])
+                    .color(Color::WHITE),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn window_area(settings: &Settings) -> Rect {
+    Rect::new(0.0, 0.0, settings.window_width, settings.window_height)
+}
@@ -1 +1,5 @@
-pub mod status_strip;
\ No newline at end of file
+pub mod graph;
+pub mod inspect_panel;
+pub mod layout;
+pub mod menu;
+pub mod status_strip;
@@ -1,31 +1,130 @@
 use ggez::{Context, GameResult};
-use ggez::graphics::{self, Canvas, Color, Text, TextFragment};
+use ggez::graphics::{self, Canvas, Color, DrawMode, Mesh, Rect, Text, TextFragment};
 
-use crate::game_stats::GameStats;
+use crate::game_controller::GameController;
 use crate::settings::Settings;
 
+use super::layout::{Layout, GAME_SPEEDS};
+
+const TEXT_SIZE: f32 = 24.0;
+const PADDING: f32 = 10.0;
+const ENTRY_SPACING: f32 = 28.0;
+
+/// Draws the HUD: the stats, listed left to right for as long as they fit, and on the right
+/// the next wave's composition, the button that calls it and a button per game speed.
 pub fn render_status_strip(
     ctx: &mut Context,
     canvas: &mut Canvas,
-    game_stats: &GameStats,
+    game_controller: &GameController,
     settings: &Settings,
+    layout: &Layout,
+    speed_index: usize,
 ) -> GameResult {
-    let strip_height = 40.0;
-    let background = graphics::Mesh::new_rectangle(
-        ctx,
-        graphics::DrawMode::fill(),
-        graphics::Rect::new(0.0, 0.0, settings.window_width, strip_height),
-        Color::new(0.2, 0.2, 0.2, 1.0),
-    )?;
+    let scale = layout.scale;
+    let background = Mesh::new_rectangle(ctx, DrawMode::fill(), layout.hud, Color::new(0.2, 0.2, 0.2, 1.0))?;
     canvas.draw(&background, graphics::DrawParam::default());
 
-    let money_text = Text::new(TextFragment::new(format!("Money: ${}", game_stats.money)).scale(24.0));
-    let wave_text = Text::new(TextFragment::new(format!("Wave: {}", game_stats.wave)).scale(24.0));
-    let kills_text = Text::new(TextFragment::new(format!("Kills: {}", game_stats.enemies_killed)).scale(24.0));
+    let game_stats = &game_controller.game_stats;
+    let mut entries = vec![
+        format!("Money: ${}", game_stats.economy.money()),
+        format!("Lives: {}", game_stats.lives),
+        match settings.last_wave() {
+            Some(last) => format!("Wave: {}/{}", game_stats.wave, last),
+            None => format!("Wave: {}", game_stats.wave),
+        },
+    ];
+    if let Some(seconds) = game_controller.time_to_next_wave(settings) {
+        entries.push(format!("Next wave: {:.0}s", seconds.ceil()));
+    }
+    entries.push(game_stats.difficulty.name().to_string());
+    entries.push(format!("Kills: {}", game_stats.enemies_killed));
+    entries.push(format!("Seed: {}", settings.seed));
+
+    let stats_area = layout.stats_area();
+    let preview = game_controller.next_wave_preview(settings);
+    let mut stats_right = stats_area.right();
+    if let Some(wave) = &preview {
+        let enemies: Vec<String> =
+            wave.enemies.iter().map(|(enemy, count)| format!("{} {}", count, enemy.name())).collect();
+        let line = format!(
+            "Wave {}: {} {:.0}hp {:.1}/s ${}",
+            wave.wave,
+            enemies.join(", "),
+            wave.health,
+            wave.speed,
+            wave.bounty
+        );
+        let text = Text::new(TextFragment::new(line).scale(TEXT_SIZE * scale));
+        let text_dims = text.measure(ctx)?;
+        // The preview gives way to the stats on narrow windows
+        if text_dims.x <= stats_area.w / 2.0 {
+            stats_right -= text_dims.x + PADDING * scale;
+            canvas.draw(
+                &text,
+                graphics::DrawParam::default()
+                    .dest([stats_right, stats_area.y + (stats_area.h - text_dims.y) / 2.0])
+                    .color(Color::new(1.0, 0.75, 0.5, 1.0)),
+            );
+            stats_right -= ENTRY_SPACING * scale;
+        }
+    }
+
+    let mut x = stats_area.x + PADDING * scale;
+    for entry in entries {
+        let text = Text::new(TextFragment::new(entry).scale(TEXT_SIZE * scale));
+        let text_dims = text.measure(ctx)?;
+        if x + text_dims.x > stats_right {
+            break;
+        }
+        canvas.draw(
+            &text,
+            graphics::DrawParam::default()
+                .dest([x, stats_area.y + (stats_area.h - text_dims.y) / 2.0])
+                .color(Color::WHITE),
+        );
+        x += text_dims.x + ENTRY_SPACING * scale;
+    }
+
+    let (label, color) = match preview {
+        Some(_) => (
+            format!("Call +${}", game_controller.early_call_bonus(settings)),
+            Color::new(0.35, 0.3, 0.2, 1.0),
+        ),
+        None => ("Call wave".to_string(), Color::new(0.25, 0.25, 0.25, 1.0)),
+    };
+    draw_button(ctx, canvas, layout.call_wave_button(), &label, color, scale)?;
+
+    for (index, speed) in GAME_SPEEDS.iter().enumerate() {
+        let rect = layout.speed_button(index);
+        let color = if index == speed_index {
+            Color::new(0.45, 0.45, 0.55, 1.0)
+        } else {
+            Color::new(0.3, 0.3, 0.3, 1.0)
+        };
+        draw_button(ctx, canvas, rect, &format!("{}x", speed), color, scale)?;
+    }
 
-    canvas.draw(&money_text, graphics::DrawParam::default().dest([10.0, 5.0]).color(Color::WHITE));
-    canvas.draw(&wave_text, graphics::DrawParam::default().dest([settings.window_width / 2.0 - 50.0, 5.0]).color(Color::WHITE));
-    canvas.draw(&kills_text, graphics::DrawParam::default().dest([settings.window_width - 150.0, 5.0]).color(Color::WHITE));
+    Ok(())
+}
+
+fn draw_button(
+    ctx: &mut Context,
+    canvas: &mut Canvas,
+    rect: Rect,
+    label: &str,
+    color: Color,
+    scale: f32,
+) -> GameResult {
+    let button = Mesh::new_rectangle(ctx, DrawMode::fill(), rect, color)?;
+    canvas.draw(&button, graphics::DrawParam::default());
 
+    let text = Text::new(TextFragment::new(label).scale(TEXT_SIZE * scale));
+    let text_dims = text.measure(ctx)?;
+    canvas.draw(
+        &text,
+        graphics::DrawParam::default()
+            .dest([rect.x + (rect.w - text_dims.x) / 2.0, rect.y + (rect.h - text_dims.y) / 2.0])
+            .color(Color::WHITE),
+    );
     Ok(())
-}
\ No newline at end of file
+}
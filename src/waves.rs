@@ -0,0 +1,332 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::settings::{read_text_file, Settings, SettingsError};
+
+pub const DEFAULT_WAVES_PATH: &str = "waves.toml";
+
+/// Runners and brutes added to procedural waves: enemies per regular enemy of the wave, and
+/// multipliers for spacing, health and speed.
+const RUNNER: Archetype = Archetype { enemy: EnemyKind::Runner, share: 0.5, spacing: 0.4, health: 0.5, speed: 1.8 };
+const BRUTE: Archetype = Archetype { enemy: EnemyKind::Brute, share: 0.2, spacing: 2.5, health: 4.0, speed: 0.6 };
+
+struct Archetype {
+    enemy: EnemyKind,
+    share: f32,
+    spacing: f32,
+    health: f32,
+    speed: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnemyKind {
+    Grunt,
+    /// Fast and frail.
+    Runner,
+    /// Slow and tough.
+    Brute,
+}
+
+impl EnemyKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Grunt => "Grunt",
+            EnemyKind::Runner => "Runner",
+            EnemyKind::Brute => "Brute",
+        }
+    }
+}
+
+/// Enemies of one kind released at a steady pace.
+#[derive(Clone, Copy)]
+pub struct SpawnGroup {
+    pub enemy: EnemyKind,
+    pub count: u32,
+    /// Seconds between two enemies of the group.
+    pub spacing: f32,
+    /// Seconds from the start of the wave to the first enemy of the group.
+    pub delay: f32,
+    /// Multipliers applied to `enemy_health` and `enemy_speed`.
+    pub health: f32,
+    pub speed: f32,
+}
+
+#[derive(Clone)]
+pub struct WaveDefinition {
+    pub groups: Vec<SpawnGroup>,
+}
+
+/// One enemy of a wave, due `time` seconds after the wave starts.
+#[derive(Clone, Copy)]
+pub struct ScheduledSpawn {
+    pub time: f32,
+    pub enemy: EnemyKind,
+    pub health: f32,
+    pub speed: f32,
+}
+
+impl WaveDefinition {
+    /// Every enemy of the wave in spawn order. Spacing and delays are multiplied by
+    /// `time_scale`.
+    pub fn schedule(&self, time_scale: f32) -> Vec<ScheduledSpawn> {
+        let mut spawns: Vec<ScheduledSpawn> = self
+            .groups
+            .iter()
+            .flat_map(|group| {
+                (0..group.count).map(move |index| ScheduledSpawn {
+                    time: (group.delay + index as f32 * group.spacing) * time_scale,
+                    enemy: group.enemy,
+                    health: group.health,
+                    speed: group.speed,
+                })
+            })
+            .collect();
+        // Stable, so enemies due at the same time keep the order of their groups
+        spawns.sort_by(|a, b| a.time.total_cmp(&b.time));
+        spawns
+    }
+}
+
+/// The waves of a game: those listed in the wave file, followed by procedural waves that keep
+/// growing for as long as the game lasts.
+#[derive(Clone, Default)]
+pub struct WaveScript {
+    waves: Vec<WaveDefinition>,
+}
+
+impl WaveScript {
+    /// Loads the wave file. Without an explicit path, `waves.toml` is used if it exists;
+    /// otherwise every wave is procedural.
+    pub fn load(path: Option<&Path>) -> Result<Self, SettingsError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => (PathBuf::from(DEFAULT_WAVES_PATH), false),
+        };
+        match read_text_file(&path, required)? {
+            Some(contents) => Self::parse(&contents).map_err(|message| SettingsError::Parse { path, message }),
+            None => Ok(WaveScript::default()),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Self, String> {
+        let file: WaveScriptFile = toml::from_str(contents).map_err(|err| err.to_string())?;
+        let mut waves = Vec::with_capacity(file.wave.len());
+        for (index, wave) in file.wave.iter().enumerate() {
+            let line = line_of(contents, wave.span());
+            if wave.get_ref().group.is_empty() {
+                return Err(format!("line {}: wave {} has no groups", line, index + 1));
+            }
+            let groups = wave
+                .get_ref()
+                .group
+                .iter()
+                .map(|group| group.get_ref().validate().map_err(|message| {
+                    format!("line {}: {}", line_of(contents, group.span()), message)
+                }))
+                .collect::<Result<_, _>>()?;
+            waves.push(WaveDefinition { groups });
+        }
+        Ok(WaveScript { waves })
+    }
+
+    /// The definition of wave `wave`, counting from 1. Past the scripted waves, the last one
+    /// (or the wave described by the settings when there is no script) is repeated with more,
+    /// tougher and faster enemies every wave, joined by runners and brutes from the waves set
+    /// in the settings.
+    pub fn wave(&self, settings: &Settings, wave: u32) -> WaveDefinition {
+        if let Some(definition) = self.waves.get(wave as usize - 1) {
+            return definition.clone();
+        }
+        let (base, extra_waves) = match self.waves.last() {
+            Some(last) => (last.clone(), wave as usize - self.waves.len()),
+            None => (
+                WaveDefinition {
+                    groups: vec![SpawnGroup {
+                        enemy: EnemyKind::Grunt,
+                        count: settings.enemies_per_wave,
+                        spacing: settings.enemy_spawn_interval,
+                        delay: 0.0,
+                        health: 1.0,
+                        speed: 1.0,
+                    }],
+                },
+                wave as usize - 1,
+            ),
+        };
+        let health_scale = 1.0 + settings.wave_health_growth * extra_waves as f32;
+        let count_scale = 1.0 + settings.wave_count_growth * extra_waves as f32;
+        let speed_scale = 1.0 + settings.wave_speed_growth * extra_waves as f32;
+        let mut groups: Vec<SpawnGroup> = base
+            .groups
+            .iter()
+            .map(|group| SpawnGroup {
+                count: (group.count as f32 * count_scale).round() as u32,
+                health: group.health * health_scale,
+                speed: group.speed * speed_scale,
+                ..*group
+            })
+            .collect();
+
+        // Archetypes follow the first group, starting halfway through it
+        let lead = groups[0];
+        for (archetype, first_wave) in [(RUNNER, settings.runner_wave), (BRUTE, settings.brute_wave)] {
+            if wave >= first_wave {
+                groups.push(SpawnGroup {
+                    enemy: archetype.enemy,
+                    count: ((lead.count as f32 * archetype.share).round() as u32).max(1),
+                    spacing: lead.spacing * archetype.spacing,
+                    delay: lead.delay + lead.spacing * lead.count as f32 / 2.0,
+                    health: lead.health * archetype.health,
+                    speed: lead.speed * archetype.speed,
+                });
+            }
+        }
+        WaveDefinition { groups }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveScriptFile {
+    #[serde(default)]
+    wave: Vec<Spanned<WaveFile>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveFile {
+    #[serde(default)]
+    group: Vec<Spanned<GroupFile>>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GroupFile {
+    enemy: EnemyKind,
+    count: u32,
+    spacing: f32,
+    delay: f32,
+    health: f32,
+    speed: f32,
+    /// Entrance the group comes from. Levels have a single one, so only 0 is accepted.
+    spawn: usize,
+}
+
+impl Default for GroupFile {
+    fn default() -> Self {
+        GroupFile {
+            enemy: EnemyKind::Grunt,
+            count: 1,
+            spacing: 1.0,
+            delay: 0.0,
+            health: 1.0,
+            speed: 1.0,
+            spawn: 0,
+        }
+    }
+}
+
+impl GroupFile {
+    fn validate(&self) -> Result<SpawnGroup, String> {
+        if self.count == 0 {
+            return Err("count must be at least 1".to_string());
+        }
+        if !self.spacing.is_finite() || self.spacing < 0.0 {
+            return Err(format!("spacing must be zero or more seconds, got {}", self.spacing));
+        }
+        if !self.delay.is_finite() || self.delay < 0.0 {
+            return Err(format!("delay must be zero or more seconds, got {}", self.delay));
+        }
+        if !self.health.is_finite() || self.health <= 0.0 {
+            return Err(format!("health must be more than 0, got {}", self.health));
+        }
+        if !self.speed.is_finite() || self.speed <= 0.0 {
+            return Err(format!("speed must be more than 0, got {}", self.speed));
+        }
+        if self.spawn != 0 {
+            return Err(format!("spawn is {}, but levels have a single spawn, numbered 0", self.spawn));
+        }
+        Ok(SpawnGroup {
+            enemy: self.enemy,
+            count: self.count,
+            spacing: self.spacing,
+            delay: self.delay,
+            health: self.health,
+            speed: self.speed,
+        })
+    }
+}
+
+/// Line number, counting from 1, at which a span starts.
+fn line_of(contents: &str, span: Range<usize>) -> usize {
+    contents[..span.start.min(contents.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_file() {
+        let script = WaveScript::parse(
+            "[[wave]]\n\
+             [[wave.group]]\n\
+             count = 8\n\
+             spacing = 4.0\n\
+             \n\
+             [[wave]]\n\
+             [[wave.group]]\n\
+             count = 3\n\
+             [[wave.group]]\n\
+             enemy = \"grunt\"\n\
+             count = 12\n\
+             delay = 4.0\n\
+             health = 0.5\n\
+             speed = 1.5\n",
+        )
+        .unwrap();
+
+        assert_eq!(script.waves.len(), 2);
+        let first = &script.waves[0].groups;
+        assert_eq!(first.len(), 1);
+        assert_eq!((first[0].count, first[0].spacing, first[0].delay), (8, 4.0, 0.0));
+        let second = &script.waves[1].groups;
+        assert_eq!(second.len(), 2);
+        assert_eq!((second[0].count, second[0].spacing), (3, 1.0));
+        assert_eq!((second[1].count, second[1].delay, second[1].health, second[1].speed), (12, 4.0, 0.5, 1.5));
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        let error = WaveScript::parse("[[wave]]\n[[wave.group]]\ncount = 2\nsize = 3\n").err().unwrap();
+        assert!(error.contains("size"), "{}", error);
+    }
+
+    #[test]
+    fn reports_line_of_bad_value() {
+        let error = WaveScript::parse(
+            "[[wave]]\n\
+             [[wave.group]]\n\
+             count = 2\n\
+             \n\
+             [[wave]]\n\
+             [[wave.group]]\n\
+             count = 0\n",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error, "line 6: count must be at least 1");
+    }
+
+    #[test]
+    fn rejects_second_spawn() {
+        let script = WaveScript::parse("[[wave]]\n[[wave.group]]\nspawn = 0\n").unwrap();
+        assert_eq!(script.waves[0].groups.len(), 1);
+        let error = WaveScript::parse("[[wave]]\n[[wave.group]]\ncount = 2\nspawn = 1\n").err().unwrap();
+        assert_eq!(error, "line 2: spawn is 1, but levels have a single spawn, numbered 0");
+    }
+}